@@ -0,0 +1,1605 @@
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use gdal::raster::ResampleAlg;
+use gdal::Dataset;
+use gdal_dtm_exporter_lib::formats::{list_formats, ColorSpace, ExrChannels, ExrCompression, PngBitDepth, PngMode, Tonemap};
+use gdal_dtm_exporter_lib::colormap;
+use gdal_dtm_exporter_lib::filters::DownsampleMethod;
+use gdal_dtm_exporter_lib::contour::export_contours;
+use gdal_dtm_exporter_lib::mosaic::mosaic_to_temp_geotiff;
+use gdal_dtm_exporter_lib::reproject::{reproject_to_temp_geotiff, ReprojectOptions};
+use gdal_dtm_exporter_lib::terrain;
+use gdal_dtm_exporter_lib::{
+    decimation_stride_for_max_faces, elevation_histogram, export_aspect, export_dtm_to_exr,
+    export_dtm_to_exr_package, export_dtm_to_obj, export_dtm_to_ply, export_dtm_to_png,
+    export_dtm_to_raw_mmap, export_dtm_to_usda, export_hillshade, export_multiband_to_tiff,
+    export_slope, inspect_dtm, pack_channels_to_exr, plan_size_split_tiles,
+    quantization_report, validate_input, BandSelection, DtmOutputFormat, NodataAs, PlyFormat,
+};
+
+#[derive(Parser)]
+#[command(name = "gdal-dtm-exporter", about = "Export DTM/DEM rasters to OpenEXR for Houdini")]
+struct Cli {
+    /// Log errors only, overriding RUST_LOG if set. Mutually exclusive
+    /// with -v.
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Raise log verbosity above the RUST_LOG (or info) default,
+    /// overriding it if set: -v for debug, -vv for trace. Mutually
+    /// exclusive with --quiet.
+    #[arg(short, action = clap::ArgAction::Count, global = true)]
+    v: u8,
+
+    /// Emit log lines (and export progress) as one JSON object per line --
+    /// `{"level", "msg", "progress"}` -- to stderr instead of the default
+    /// colored format, for a supervising program to parse reliably.
+    /// `progress` is a 0.0-1.0 fraction on a progress update, null
+    /// otherwise.
+    #[arg(long, global = true)]
+    json_logs: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert a single DTM/DEM raster to OpenEXR.
+    Export {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        /// Overrides the derived output file's base name (the input file's
+        /// stem, by default). The extension is still set by --format.
+        /// Mutually exclusive with --output.
+        #[arg(long)]
+        output_name: Option<String>,
+
+        /// Full output file path, bypassing --output-dir/--output-name
+        /// entirely (the extension --format implies still overrides
+        /// whatever extension is given here). Its parent directory is
+        /// created if missing. Only valid for a single-file --input-dtm;
+        /// mutually exclusive with --output-name.
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        #[arg(long, default_value_t = 1)]
+        window_scale_factor: usize,
+
+        /// Pick --window-scale-factor automatically from --memory-budget
+        /// instead of using the value above, which is ignored when this is
+        /// set.
+        #[arg(long)]
+        auto_window: bool,
+
+        /// Per-tile memory budget --auto-window keeps each read window
+        /// under, in bytes.
+        #[arg(long, default_value_t = gdal_dtm_exporter_lib::DEFAULT_MEMORY_BUDGET_BYTES)]
+        memory_budget: u64,
+
+        /// Restrict the export to a MIN_X,MIN_Y,MAX_X,MAX_Y pixel-space
+        /// rectangle (max exclusive) instead of the whole raster. Mutually
+        /// exclusive with --bbox-geo.
+        #[arg(long, value_parser = parse_bbox)]
+        bbox: Option<(usize, usize, usize, usize)>,
+
+        /// Like --bbox, but MIN_X,MIN_Y,MAX_X,MAX_Y are in the dataset's own
+        /// map units (its geotransform), not pixels -- converted to a pixel
+        /// bbox via the source dataset's geotransform before exporting.
+        #[arg(long, value_parser = parse_bbox_geo)]
+        bbox_geo: Option<(f64, f64, f64, f64)>,
+
+        /// Reproject the source raster into this CRS (an EPSG code like
+        /// "EPSG:4326" or a WKT string) before exporting. Skipped with a log
+        /// line when the source is already in this CRS.
+        #[arg(long)]
+        target_srs: Option<String>,
+
+        /// Target pixel size (X,Y) in --target-srs units, for the
+        /// --target-srs warp. Defaults to roughly matching the source
+        /// raster's pixel density.
+        #[arg(long, value_parser = parse_xy)]
+        target_resolution: Option<(f64, f64)>,
+
+        /// Target output bounds (MIN_X,MIN_Y,MAX_X,MAX_Y) in --target-srs
+        /// units, for the --target-srs warp. Defaults to the source
+        /// raster's own extent, reprojected.
+        #[arg(long, value_parser = parse_bbox_geo)]
+        target_extent: Option<(f64, f64, f64, f64)>,
+
+        /// Additional tile(s) to composite alongside --input-dtm into one
+        /// raster before exporting, placed by each tile's own geotransform
+        /// (e.g. a 3x3 grid of adjacent survey tiles). Mismatched pixel
+        /// size warns and resamples; overlapping tiles warn, with the
+        /// later tile (in the order given here, after --input-dtm) winning
+        /// at the shared pixels. Requires a single-file --input-dtm, not a
+        /// batch directory.
+        #[arg(long)]
+        mosaic_with: Vec<PathBuf>,
+
+        #[arg(long)]
+        normalize: bool,
+
+        #[arg(long)]
+        overwrite: bool,
+
+        #[arg(long)]
+        flip_y: bool,
+
+        #[arg(long)]
+        flip_x: bool,
+
+        /// Normalize each tile independently (debug aid; produces visible seams).
+        #[arg(long)]
+        normalize_per_tile: bool,
+
+        /// Manual normalization lower bound, replacing the computed min.
+        /// Requires --norm-max to also be set.
+        #[arg(long)]
+        norm_min: Option<f64>,
+
+        /// Manual normalization upper bound, replacing the computed max.
+        /// Requires --norm-min to also be set.
+        #[arg(long)]
+        norm_max: Option<f64>,
+
+        /// Normalize against the LOW,HIGH percentiles (0-100) of each band's
+        /// valid samples instead of its raw min/max, so a few outlier pits
+        /// or spikes don't wash out the rest of the output. Mutually
+        /// exclusive with --norm-min/--norm-max.
+        #[arg(long, value_parser = parse_percentile_range)]
+        normalize_percentile: Option<(f64, f64)>,
+
+        /// Vertical exaggeration applied to every sampled elevation, before
+        /// normalization. Values above 1.0 stretch relief beyond the
+        /// computed range instead of being renormalized away.
+        #[arg(long, default_value_t = 1.0)]
+        vertical_exaggeration: f32,
+
+        /// Render no-data pixels as this R,G,B color instead of black.
+        #[arg(long, value_parser = parse_rgb)]
+        nodata_color: Option<[f32; 3]>,
+
+        /// What to write for a no-data sample when --nodata-color isn't set
+        /// (or always, for the per-band channels produced when --band is
+        /// unset, since --nodata-color only applies to single-band output).
+        #[arg(long, value_parser = parse_nodata_as, default_value = "zero")]
+        nodata_as: NodataAs,
+
+        /// Write a headerless memory-mapped f32 file instead of OpenEXR.
+        #[arg(long)]
+        raw_mmap: bool,
+
+        /// Run N iterations of a thermal-erosion pass before writing (raw-mmap
+        /// output only). Purely aesthetic — not a physical simulation.
+        #[arg(long, default_value_t = 0)]
+        erode: usize,
+
+        /// Height delta (per cell) above which the erosion pass redistributes
+        /// material to lower neighbors.
+        #[arg(long, default_value_t = 0.1)]
+        talus_angle: f32,
+
+        /// Write a multi-part EXR with elevation, normals and a mask instead
+        /// of a plain grayscale EXR.
+        #[arg(long)]
+        exr_package: bool,
+
+        /// Print a quantization-loss report for this output bit depth (e.g.
+        /// 8 or 16) after writing, without changing the actual output format.
+        #[arg(long)]
+        report_quantization: Option<u32>,
+
+        /// Also write a GDAL PAM .aux.xml sidecar with precomputed statistics.
+        #[arg(long)]
+        write_aux_xml: bool,
+
+        /// Also write an ESRI world file (.tfw/.pgw/.exrw) sidecar with the
+        /// source dataset's geotransform, for GIS tools that don't read
+        /// georeferencing out of the output format's own header.
+        #[arg(long)]
+        world_file: bool,
+
+        /// Also write a <output>.json sidecar recording the source path,
+        /// raster dimensions, spatial reference, normalization mode/range
+        /// and per-band min/max/no-data, for provenance tracking.
+        #[arg(long)]
+        stats_json: bool,
+
+        /// Color space for the normalized grayscale output. Raw elevation
+        /// data is always linear; this only affects how visually-intended
+        /// output gets interpreted by compositing tools. Defaults to linear.
+        #[arg(long, value_parser = parse_colorspace, default_value = "linear")]
+        colorspace: ColorSpace,
+
+        /// Tone curve applied to the normalized elevation before writing,
+        /// to pull out detail a plain linear normalization crushes on
+        /// terrain with both deep canyons and high peaks. "log" brightens
+        /// low-elevation detail; "gamma" applies --gamma as a power curve.
+        #[arg(long, value_parser = parse_tonemap, default_value = "linear")]
+        tonemap: Tonemap,
+
+        /// Exponent for --tonemap gamma; ignored otherwise.
+        #[arg(long, default_value_t = 2.2)]
+        gamma: f32,
+
+        /// Compression codec for the EXR output: none, rle, zip, zips, piz
+        /// or pxr24. Defaults to zip (16-scanline ZIP blocks), a reasonable
+        /// size/speed tradeoff for lossless float data.
+        #[arg(long, value_parser = parse_exr_compression, default_value = "zip")]
+        exr_compression: ExrCompression,
+
+        /// Channel layout for the EXR output when there's no colormap:
+        /// "gray" writes a single Y channel, a third the file size of the
+        /// default "rgb" (the same height repeated across R, G and B).
+        /// Ignored in favor of rgb when a colormap is applied.
+        #[arg(long, value_parser = parse_exr_channels, default_value = "rgb")]
+        channels: ExrChannels,
+
+        /// Build 2x/4x/8x/16x overview levels into the output GeoTIFF after
+        /// the full-resolution band is written, for fast zooming in GIS
+        /// viewers. Only applies to --format geotiff.
+        #[arg(long)]
+        build_overviews: bool,
+
+        /// Resampling algorithm used to build --build-overviews' levels.
+        #[arg(long, value_parser = parse_resample, default_value = "average")]
+        overview_resample: ResampleAlg,
+
+        /// Write the EXR output using tiled rather than scanline blocks, so
+        /// a downstream reader can seek straight to the tiles covering a
+        /// crop it wants instead of decoding full-width scanlines. Only
+        /// applies to --format exr.
+        #[arg(long)]
+        exr_tiled: bool,
+
+        /// Tile size (WIDTH,HEIGHT) for --exr-tiled. Ignored otherwise.
+        #[arg(long, value_parser = parse_tile_size, default_value = "128,128")]
+        exr_tile_size: (usize, usize),
+
+        /// Additionally write a mip pyramid into the EXR output, each level
+        /// halving the previous one's dimensions down to 1x1, for use as a
+        /// displacement texture. Only applies to --format exr.
+        #[arg(long)]
+        mipmaps: bool,
+
+        /// Map the normalized elevation through a named colormap instead of
+        /// grayscale. Only applies with --band (single-band output).
+        #[arg(long, value_parser = parse_colormap)]
+        colormap: Option<colormap::Colormap>,
+
+        /// Overlay coordinate gridlines every SPACING CRS units, for
+        /// visually checking georeferencing. Skipped when unset, and on raw
+        /// (un-normalized) elevation output.
+        #[arg(long)]
+        graticule: Option<f64>,
+
+        /// R,G,B color for the graticule overlay.
+        #[arg(long, value_parser = parse_rgb, default_value = "1,0,0")]
+        graticule_color: [f32; 3],
+
+        /// Blend opacity (0.0-1.0) for the graticule overlay.
+        #[arg(long, default_value_t = 0.5)]
+        graticule_opacity: f32,
+
+        /// Write every band into one multi-band GeoTIFF instead of OpenEXR.
+        #[arg(long)]
+        multiband_tiff: bool,
+
+        /// Offset elevations by a recognized vertical-datum metadata item
+        /// (e.g. REFERENCE_ELEVATION), when present.
+        #[arg(long)]
+        use_metadata_offset: bool,
+
+        /// Write a quantized grayscale PNG instead of OpenEXR.
+        #[arg(long)]
+        png: bool,
+
+        /// Bit depth for --png output.
+        #[arg(long, value_parser = parse_png_bit_depth, default_value = "8")]
+        png_bit_depth: PngBitDepth,
+
+        /// Whether --png output is tagged as color-managed visualization
+        /// output (sRGB/gamma chunk, via --colorspace) or left untagged as
+        /// raw data for round-tripping.
+        #[arg(long, value_parser = parse_png_mode, default_value = "visualization")]
+        png_mode: PngMode,
+
+        /// If the estimated single-file output would exceed this size (e.g.
+        /// "2GiB", "512MiB"), report the row/column tile layout that would
+        /// keep each piece under the limit, and each tile's geographic
+        /// footprint. Diagnostics only: this does not split the export into
+        /// multiple files, and the full, oversized single file is still
+        /// written -- re-run with `--bbox`/`--bbox-geo` per reported tile
+        /// footprint to actually produce split output files.
+        #[arg(long, value_parser = parse_byte_size)]
+        split_output_by_size: Option<u64>,
+
+        /// Read a 1x1 window from every band before exporting, to fail fast
+        /// on a truncated file or unreadable band instead of partway through
+        /// a long export.
+        #[arg(long)]
+        validate_input: bool,
+
+        /// Resampling algorithm used when a read window doesn't land on
+        /// whole source pixels.
+        #[arg(long, value_parser = parse_resample, default_value = "bilinear")]
+        resample: ResampleAlg,
+
+        /// Expand each tile's read window by this many pixels on every side
+        /// (clamped at the raster's edges) before resampling, discarding the
+        /// halo before writing -- gives a non-nearest --resample real
+        /// neighboring pixels at a tile boundary instead of just whatever
+        /// lies inside that tile.
+        #[arg(long, default_value_t = 0)]
+        tile_overlap: usize,
+
+        /// Fill a no-data pixel with an inverse-distance weighted mean of
+        /// the valid samples within this many pixels of it, before
+        /// normalization/colormapping runs. A void wider than this in
+        /// every direction stays no-data.
+        #[arg(long)]
+        fill_voids: Option<usize>,
+
+        /// Output encoder for the normal (non-multiband-tiff, non-raw-mmap,
+        /// non-exr-package, non-png) export path.
+        #[arg(long, value_parser = parse_output_format, default_value = "exr")]
+        format: DtmOutputFormat,
+
+        /// Add a 4th alpha channel to the EXR output, 0.0 for no-data
+        /// samples and 1.0 otherwise. Ignored by --format geotiff/png16.
+        #[arg(long)]
+        with_mask: bool,
+
+        /// Map only this 1-indexed band onto the output instead of one
+        /// channel per band. Ignored by --multiband-tiff, --raw-mmap,
+        /// --exr-package and --png, which already operate on a single band
+        /// or all bands in their own way.
+        #[arg(long)]
+        band: Option<usize>,
+
+        /// Cap the rayon thread pool used for tile reads/normalization to N
+        /// threads. Defaults to rayon's own heuristic (usually one thread
+        /// per core) when unset.
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// When --input-dtm is a directory, keep going after a file fails
+        /// instead of aborting the whole batch. Ignored for a single-file
+        /// --input-dtm.
+        #[arg(long)]
+        continue_on_error: bool,
+
+        /// Suppress the progress bar. It's also suppressed automatically
+        /// when stderr isn't a TTY (e.g. piped into a log file), so this is
+        /// mostly for forcing it off in an interactive shell.
+        #[arg(long)]
+        quiet: bool,
+    },
+
+    /// Pack three single-band rasters into one RGB OpenEXR image.
+    Pack {
+        #[arg(long)]
+        red: PathBuf,
+
+        #[arg(long)]
+        green: PathBuf,
+
+        #[arg(long)]
+        blue: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        #[arg(long, default_value = "packed")]
+        output_name: String,
+
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Export a raster as a PLY/OBJ mesh instead of OpenEXR.
+    Ply {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        /// Vertical exaggeration applied to every sampled elevation.
+        #[arg(long, default_value_t = 1.0)]
+        y_scale: f32,
+
+        #[arg(long, default_value_t = 1)]
+        window_scale_factor: usize,
+
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Mesh file format to write.
+        #[arg(long, value_parser = parse_mesh_format, default_value = "ply")]
+        mesh_format: MeshFormat,
+
+        /// Write ASCII PLY instead of the default binary_little_endian
+        /// encoding. Much larger and slower for a dense terrain mesh; kept
+        /// as an opt-in fallback for tools that can't read binary PLY.
+        /// Only applies to --mesh-format ply.
+        #[arg(long)]
+        ascii: bool,
+
+        /// Color each vertex by elevation through this named colormap
+        /// (viridis, magma, turbo, terrain), adding red/green/blue uchar
+        /// properties to the PLY. Omit for the plain x/y/z-only PLY. Only
+        /// applies to --mesh-format ply.
+        #[arg(long, value_parser = parse_colormap)]
+        ply_colors: Option<colormap::Colormap>,
+
+        /// Write `vt` texture coordinates mapping grid position to
+        /// [0, 1]x[0, 1], so a texture can be applied to the mesh. Only
+        /// applies to --mesh-format obj.
+        #[arg(long)]
+        with_uvs: bool,
+
+        /// Stride-subsample the grid before triangulating: keep every Nth
+        /// row/column instead of every sampled cell, for a lighter proxy
+        /// mesh. Mutually exclusive with --max-faces.
+        #[arg(long)]
+        decimate: Option<usize>,
+
+        /// Pick the largest stride-subsample (see --decimate) that keeps
+        /// the mesh at or under this many faces, instead of naming a
+        /// stride directly. Mutually exclusive with --decimate.
+        #[arg(long)]
+        max_faces: Option<usize>,
+
+        /// How --decimate/--max-faces prepares elevations before striding.
+        /// `average` picks raw elevations (no filtering); `gaussian`/
+        /// `lanczos` low-pass the full-resolution grid first, sigma/radius
+        /// derived from the decimation stride, which suppresses aliasing
+        /// that plain striding lets through on rugged terrain at the cost
+        /// of a slightly softer proxy.
+        #[arg(long, value_parser = parse_downsample_method, default_value = "average")]
+        downsample_method: DownsampleMethod,
+
+        /// Merge vertices that land within this world-space tolerance of
+        /// each other (quantized position, same units as the mesh itself)
+        /// and re-index faces to match, closing cracks that mosaic-derived
+        /// or tiled mesh input leaves at shared edges. Not supported
+        /// together with --with-uvs (welding would desync the UVs).
+        #[arg(long)]
+        weld_tolerance: Option<f32>,
+
+        /// Thin faces over low-curvature (flat) terrain instead of
+        /// uniformly striding, targeting --target-vertices total kept
+        /// vertices. Requires --target-vertices; mutually exclusive with
+        /// --decimate/--max-faces and with --with-uvs.
+        #[arg(long)]
+        decimate_adaptive: bool,
+
+        /// Vertex budget for --decimate-adaptive.
+        #[arg(long)]
+        target_vertices: Option<usize>,
+
+        /// Invert the row coordinate, so the mesh isn't upside-down in a
+        /// tool that assumes a bottom-left rather than GDAL's top-left row
+        /// origin. See --flip-y on the export subcommand.
+        #[arg(long)]
+        flip_y: bool,
+    },
+
+    /// Trace vector contour lines instead of exporting a raster.
+    Contours {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        /// Elevation spacing between contour lines, in the input's own
+        /// elevation units.
+        #[arg(long)]
+        contours: f64,
+
+        /// Elevation offset for the first contour line; subsequent lines
+        /// sit at contour_base + N * --contours.
+        #[arg(long, default_value_t = 0.0)]
+        contour_base: f64,
+
+        /// Write an ESRI Shapefile instead of the default GeoJSON.
+        #[arg(long)]
+        shapefile: bool,
+    },
+
+    /// Render a grayscale hillshade instead of exporting raw elevation.
+    Hillshade {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Sun direction, in compass degrees (0 = north, clockwise).
+        #[arg(long, default_value_t = 315.0)]
+        azimuth: f32,
+
+        /// Sun height above the horizon, in degrees (0 = horizon, 90 = overhead).
+        #[arg(long, default_value_t = 45.0)]
+        altitude: f32,
+
+        /// Exaggerates the surface gradient before shading, the same way
+        /// `gdaldem hillshade`'s `-z` does.
+        #[arg(long, default_value_t = 1.0)]
+        z_factor: f32,
+
+        /// Output format; only exr and png16 are supported.
+        #[arg(long, value_parser = parse_output_format, default_value = "exr")]
+        format: DtmOutputFormat,
+    },
+
+    /// Render a slope raster (angle or percent rise) instead of exporting
+    /// raw elevation.
+    Slope {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        #[arg(long)]
+        overwrite: bool,
+
+        #[arg(long, value_parser = parse_slope_units, default_value = "degrees")]
+        slope_units: terrain::SlopeUnits,
+
+        /// Output format; only exr and png16 are supported.
+        #[arg(long, value_parser = parse_output_format, default_value = "exr")]
+        format: DtmOutputFormat,
+    },
+
+    /// Render an aspect raster (downslope-facing compass direction) instead
+    /// of exporting raw elevation.
+    Aspect {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Output format; only exr and png16 are supported.
+        #[arg(long, value_parser = parse_output_format, default_value = "exr")]
+        format: DtmOutputFormat,
+    },
+
+    /// List the output formats this build supports.
+    ListFormats {
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a dataset's driver, CRS, dimensions and per-band min/max/
+    /// no-data, without exporting anything.
+    Info {
+        #[arg(long)]
+        input_dtm: PathBuf,
+
+        /// Emit the report as JSON instead of plain text.
+        #[arg(long)]
+        json: bool,
+
+        /// Also compute an elevation histogram over band 1 with this many
+        /// buckets, printed as an ASCII bar chart (or, with
+        /// --histogram-out, written there instead as JSON/CSV).
+        #[arg(long)]
+        histogram: Option<usize>,
+
+        /// Write the --histogram to this path instead of printing an ASCII
+        /// chart. Format is picked from the extension: ".csv" for CSV,
+        /// anything else for JSON. Requires --histogram.
+        #[arg(long)]
+        histogram_out: Option<PathBuf>,
+    },
+}
+
+fn parse_colorspace(s: &str) -> Result<ColorSpace, String> {
+    match s {
+        "linear" => Ok(ColorSpace::Linear),
+        "srgb" => Ok(ColorSpace::Srgb),
+        other => Err(format!("expected linear or srgb, got {other}")),
+    }
+}
+
+fn parse_tonemap(s: &str) -> Result<Tonemap, String> {
+    match s {
+        "linear" => Ok(Tonemap::Linear),
+        "log" => Ok(Tonemap::Log),
+        "gamma" => Ok(Tonemap::Gamma),
+        other => Err(format!("expected linear, log or gamma, got {other}")),
+    }
+}
+
+fn parse_exr_compression(s: &str) -> Result<ExrCompression, String> {
+    match s {
+        "none" => Ok(ExrCompression::None),
+        "rle" => Ok(ExrCompression::Rle),
+        "zip" => Ok(ExrCompression::Zip),
+        "zips" => Ok(ExrCompression::Zips),
+        "piz" => Ok(ExrCompression::Piz),
+        "pxr24" => Ok(ExrCompression::Pxr24),
+        other => Err(format!("expected none, rle, zip, zips, piz or pxr24, got {other}")),
+    }
+}
+
+fn parse_exr_channels(s: &str) -> Result<ExrChannels, String> {
+    match s {
+        "gray" => Ok(ExrChannels::Gray),
+        "rgb" => Ok(ExrChannels::Rgb),
+        other => Err(format!("expected gray or rgb, got {other}")),
+    }
+}
+
+/// Parses a size like "2GiB", "512MiB" or a bare byte count into a byte total.
+fn parse_byte_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (number, multiplier) = if let Some(prefix) = s.strip_suffix("GiB") {
+        (prefix, 1024 * 1024 * 1024)
+    } else if let Some(prefix) = s.strip_suffix("MiB") {
+        (prefix, 1024 * 1024)
+    } else if let Some(prefix) = s.strip_suffix("KiB") {
+        (prefix, 1024)
+    } else if let Some(prefix) = s.strip_suffix('B') {
+        (prefix, 1)
+    } else {
+        (s, 1)
+    };
+
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid size: {s}"))?;
+    Ok((value * multiplier as f64).round() as u64)
+}
+
+fn parse_resample(s: &str) -> Result<ResampleAlg, String> {
+    match s {
+        "nearest" => Ok(ResampleAlg::NearestNeighbour),
+        "bilinear" => Ok(ResampleAlg::Bilinear),
+        "cubic" => Ok(ResampleAlg::Cubic),
+        "average" => Ok(ResampleAlg::Average),
+        "lanczos" => Ok(ResampleAlg::Lanczos),
+        other => Err(format!("expected nearest, bilinear, cubic, average or lanczos, got {other}")),
+    }
+}
+
+/// GDAL's `BuildOverviews` takes its resampling mode as a name string
+/// rather than the [`ResampleAlg`] enum a read window's resample uses --
+/// mapped here instead of adding a second, string-based CLI argument for
+/// the same five choices `--overview-resample` already offers through
+/// `parse_resample`.
+fn overview_resample_name(resample: ResampleAlg) -> &'static str {
+    match resample {
+        ResampleAlg::NearestNeighbour => "NEAREST",
+        ResampleAlg::Bilinear => "BILINEAR",
+        ResampleAlg::Cubic => "CUBIC",
+        ResampleAlg::Average => "AVERAGE",
+        ResampleAlg::Lanczos => "LANCZOS",
+        _ => "AVERAGE",
+    }
+}
+
+fn parse_nodata_as(s: &str) -> Result<NodataAs, String> {
+    match s {
+        "nan" => Ok(NodataAs::Nan),
+        "zero" => Ok(NodataAs::Zero),
+        other => Err(format!("expected nan or zero, got {other}")),
+    }
+}
+
+/// Deletes the wrapped temp file (if any) when dropped, so a --target-srs
+/// reprojection's temporary GeoTIFF doesn't outlive the export that used it,
+/// even on an early `?` return.
+struct TempFileGuard(Option<PathBuf>);
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        if let Some(path) = &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Extension allowlist used to pick out raster files when `--input-dtm`
+/// names a directory, so a stray `.json`/`.xml` sidecar sitting next to the
+/// rasters in the same folder doesn't get fed to GDAL as an export input.
+fn is_raster_like(path: &std::path::Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()),
+        Some(ext) if matches!(ext.as_str(), "tif" | "tiff" | "img" | "dem" | "hgt" | "asc" | "vrt")
+    )
+}
+
+/// Converts a `(min_x, min_y, max_x, max_y)` bbox in `geo_transform`'s map
+/// units into a pixel-space bbox, by inverting the affine transform at all
+/// four corners and taking their bounding rectangle -- safe even when
+/// `pixel_height` is negative (the common north-up case), which would
+/// otherwise flip which geo coordinate is "min" and which is "max" in pixel
+/// space.
+fn geo_bbox_to_pixel_bbox(
+    geo_transform: &[f64; 6],
+    (min_x, min_y, max_x, max_y): (f64, f64, f64, f64),
+) -> Result<(usize, usize, usize, usize), String> {
+    let det = geo_transform[1] * geo_transform[5] - geo_transform[2] * geo_transform[4];
+    if det.abs() < f64::EPSILON {
+        return Err("dataset's geotransform is singular; can't convert a map-unit bbox to pixels".to_string());
+    }
+
+    let to_pixel = |world_x: f64, world_y: f64| -> (f64, f64) {
+        let dx = world_x - geo_transform[0];
+        let dy = world_y - geo_transform[3];
+        let px = (geo_transform[5] * dx - geo_transform[2] * dy) / det;
+        let py = (geo_transform[1] * dy - geo_transform[4] * dx) / det;
+        (px, py)
+    };
+
+    let corners = [
+        to_pixel(min_x, min_y),
+        to_pixel(min_x, max_y),
+        to_pixel(max_x, min_y),
+        to_pixel(max_x, max_y),
+    ];
+    let px_min = corners.iter().map(|(x, _)| *x).fold(f64::INFINITY, f64::min);
+    let px_max = corners.iter().map(|(x, _)| *x).fold(f64::NEG_INFINITY, f64::max);
+    let py_min = corners.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+    let py_max = corners.iter().map(|(_, y)| *y).fold(f64::NEG_INFINITY, f64::max);
+
+    Ok((
+        px_min.floor().max(0.0) as usize,
+        py_min.floor().max(0.0) as usize,
+        px_max.ceil().max(0.0) as usize,
+        py_max.ceil().max(0.0) as usize,
+    ))
+}
+
+fn parse_output_format(s: &str) -> Result<DtmOutputFormat, String> {
+    match s {
+        "exr" => Ok(DtmOutputFormat::Exr),
+        "geotiff" => Ok(DtmOutputFormat::GeoTiff),
+        "png16" => Ok(DtmOutputFormat::Png16),
+        other => Err(format!("expected exr, geotiff or png16, got {other}")),
+    }
+}
+
+fn parse_png_bit_depth(s: &str) -> Result<PngBitDepth, String> {
+    match s {
+        "8" => Ok(PngBitDepth::Eight),
+        "16" => Ok(PngBitDepth::Sixteen),
+        other => Err(format!("expected 8 or 16, got {other}")),
+    }
+}
+
+fn parse_png_mode(s: &str) -> Result<PngMode, String> {
+    match s {
+        "visualization" => Ok(PngMode::Visualization),
+        "data" => Ok(PngMode::Data),
+        other => Err(format!("expected visualization or data, got {other}")),
+    }
+}
+
+fn parse_rgb(s: &str) -> Result<[f32; 3], String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 3 {
+        return Err(format!("expected R,G,B, got {s}"));
+    }
+    let mut rgb = [0.0f32; 3];
+    for (i, part) in parts.iter().enumerate() {
+        rgb[i] = part.trim().parse().map_err(|_| format!("invalid channel value: {part}"))?;
+    }
+    Ok(rgb)
+}
+
+fn parse_colormap(s: &str) -> Result<colormap::Colormap, String> {
+    match s {
+        "viridis" => Ok(colormap::Colormap::Viridis),
+        "magma" => Ok(colormap::Colormap::Magma),
+        "turbo" => Ok(colormap::Colormap::Turbo),
+        "terrain" => Ok(colormap::Colormap::Terrain),
+        other => Err(format!("expected viridis, magma, turbo or terrain, got {other}")),
+    }
+}
+
+/// Mesh file format for [`Command::Ply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MeshFormat {
+    Ply,
+    Obj,
+    Usda,
+}
+
+fn parse_mesh_format(s: &str) -> Result<MeshFormat, String> {
+    match s {
+        "ply" => Ok(MeshFormat::Ply),
+        "obj" => Ok(MeshFormat::Obj),
+        "usda" => Ok(MeshFormat::Usda),
+        other => Err(format!("expected ply, obj or usda, got {other}")),
+    }
+}
+
+fn parse_downsample_method(s: &str) -> Result<DownsampleMethod, String> {
+    match s {
+        "average" => Ok(DownsampleMethod::Average),
+        "gaussian" => Ok(DownsampleMethod::Gaussian),
+        "lanczos" => Ok(DownsampleMethod::Lanczos),
+        other => Err(format!("expected average, gaussian or lanczos, got {other}")),
+    }
+}
+
+fn parse_slope_units(s: &str) -> Result<terrain::SlopeUnits, String> {
+    match s {
+        "degrees" => Ok(terrain::SlopeUnits::Degrees),
+        "percent" => Ok(terrain::SlopeUnits::Percent),
+        other => Err(format!("expected degrees or percent, got {other}")),
+    }
+}
+
+fn parse_bbox(s: &str) -> Result<(usize, usize, usize, usize), String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 4 {
+        return Err(format!("expected MIN_X,MIN_Y,MAX_X,MAX_Y, got {s}"));
+    }
+    let mut values = [0usize; 4];
+    for (i, part) in parts.iter().enumerate() {
+        values[i] = part.trim().parse().map_err(|_| format!("invalid bbox coordinate: {part}"))?;
+    }
+    let (min_x, min_y, max_x, max_y) = (values[0], values[1], values[2], values[3]);
+    if max_x <= min_x || max_y <= min_y {
+        return Err(format!("expected MIN_X < MAX_X and MIN_Y < MAX_Y, got {s}"));
+    }
+    Ok((min_x, min_y, max_x, max_y))
+}
+
+fn parse_tile_size(s: &str) -> Result<(usize, usize), String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 2 {
+        return Err(format!("expected WIDTH,HEIGHT, got {s}"));
+    }
+    let width: usize = parts[0].trim().parse().map_err(|_| format!("invalid tile width: {}", parts[0]))?;
+    let height: usize = parts[1].trim().parse().map_err(|_| format!("invalid tile height: {}", parts[1]))?;
+    if width == 0 || height == 0 {
+        return Err(format!("tile size must be non-zero, got {s}"));
+    }
+    Ok((width, height))
+}
+
+fn parse_xy(s: &str) -> Result<(f64, f64), String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 2 {
+        return Err(format!("expected X,Y, got {s}"));
+    }
+    let x: f64 = parts[0].trim().parse().map_err(|_| format!("invalid X: {}", parts[0]))?;
+    let y: f64 = parts[1].trim().parse().map_err(|_| format!("invalid Y: {}", parts[1]))?;
+    Ok((x, y))
+}
+
+fn parse_bbox_geo(s: &str) -> Result<(f64, f64, f64, f64), String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 4 {
+        return Err(format!("expected MIN_X,MIN_Y,MAX_X,MAX_Y, got {s}"));
+    }
+    let mut values = [0.0f64; 4];
+    for (i, part) in parts.iter().enumerate() {
+        values[i] = part.trim().parse().map_err(|_| format!("invalid bbox coordinate: {part}"))?;
+    }
+    Ok((values[0], values[1], values[2], values[3]))
+}
+
+fn parse_percentile_range(s: &str) -> Result<(f64, f64), String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 2 {
+        return Err(format!("expected LOW,HIGH percentiles, got {s}"));
+    }
+    let low: f64 = parts[0].trim().parse().map_err(|_| format!("invalid low percentile: {}", parts[0]))?;
+    let high: f64 = parts[1].trim().parse().map_err(|_| format!("invalid high percentile: {}", parts[1]))?;
+    if !(0.0..=100.0).contains(&low) || !(0.0..=100.0).contains(&high) || low >= high {
+        return Err(format!("expected 0 <= low < high <= 100, got {low},{high}"));
+    }
+    Ok((low, high))
+}
+
+/// Configures `env_logger`'s filter before `init()`, rather than relying on
+/// `RUST_LOG` alone: `--quiet` sets `error`, and each repeated `-v` bumps
+/// the level from `info` up through `debug` to `trace`. Either flag
+/// overrides a `RUST_LOG` the caller may have set -- an explicit flag on
+/// the command line should win over an environment variable set for some
+/// other, unrelated reason. With neither flag, `RUST_LOG` (falling back to
+/// `info`) still applies, same as before.
+///
+/// `json_logs` switches the output format to one JSON object per line
+/// (`{"level", "msg", "progress": null}`) instead of `env_logger`'s default
+/// colored format, leaving the level filtering above untouched either way.
+/// A `--json-logs` progress update is emitted separately, via
+/// [`log_progress_json`].
+fn init_logging(quiet: bool, verbosity: u8, json_logs: bool) {
+    let mut builder = env_logger::Builder::new();
+
+    if quiet {
+        builder.filter_level(log::LevelFilter::Error);
+    } else if verbosity > 0 {
+        let level = match verbosity {
+            1 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        };
+        builder.filter_level(level);
+    } else {
+        builder.parse_env(env_logger::Env::default().default_filter_or("info"));
+    }
+
+    if json_logs {
+        builder.format(|buf, record| {
+            use std::io::Write;
+            let line = serde_json::json!({
+                "level": record.level().as_str(),
+                "msg": record.args().to_string(),
+                "progress": null,
+            });
+            writeln!(buf, "{line}")
+        });
+    }
+
+    builder.init();
+}
+
+/// Writes one `{"level", "msg", "progress"}` JSON line straight to stderr,
+/// for a `--json-logs` progress update -- bypassing the `log` crate (and
+/// its formatter installed by [`init_logging`]) entirely, since a progress
+/// update fires far more often than we'd want to push through a `log::Record`.
+fn log_progress_json(fraction: f32) {
+    eprintln!(
+        "{}",
+        serde_json::json!({ "level": "INFO", "msg": "progress", "progress": fraction })
+    );
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    if cli.quiet && cli.v > 0 {
+        return Err(eyre::eyre!("--quiet and -v are mutually exclusive"));
+    }
+    let json_logs = cli.json_logs;
+    init_logging(cli.quiet, cli.v, json_logs);
+
+    match cli.command {
+        Command::Export {
+            input_dtm,
+            output_dir,
+            output_name,
+            output,
+            window_scale_factor,
+            auto_window,
+            memory_budget,
+            bbox,
+            bbox_geo,
+            target_srs,
+            target_resolution,
+            target_extent,
+            mosaic_with,
+            normalize,
+            overwrite,
+            flip_y,
+            flip_x,
+            normalize_per_tile,
+            norm_min,
+            norm_max,
+            normalize_percentile,
+            vertical_exaggeration,
+            nodata_color,
+            nodata_as,
+            raw_mmap,
+            erode,
+            talus_angle,
+            exr_package,
+            report_quantization,
+            write_aux_xml,
+            world_file,
+            stats_json,
+            colorspace,
+            tonemap,
+            gamma,
+            exr_compression,
+            channels,
+            build_overviews,
+            overview_resample,
+            exr_tiled,
+            exr_tile_size,
+            mipmaps,
+            colormap,
+            graticule,
+            graticule_color,
+            graticule_opacity,
+            multiband_tiff,
+            use_metadata_offset,
+            png,
+            png_bit_depth,
+            png_mode,
+            split_output_by_size,
+            validate_input: should_validate_input,
+            resample,
+            tile_overlap,
+            fill_voids,
+            band,
+            format,
+            with_mask,
+            threads,
+            continue_on_error,
+            quiet,
+        } => {
+            let band_selection = match band {
+                Some(index) => BandSelection::Single(index),
+                None => BandSelection::All,
+            };
+
+            let manual_range = match (norm_min, norm_max) {
+                (Some(min), Some(max)) => Some((min, max)),
+                (None, None) => None,
+                _ => return Err(eyre::eyre!("--norm-min and --norm-max must both be set together")),
+            };
+
+            if manual_range.is_some() && normalize_percentile.is_some() {
+                return Err(eyre::eyre!(
+                    "--norm-min/--norm-max and --normalize-percentile are mutually exclusive"
+                ));
+            }
+
+            if bbox.is_some() && bbox_geo.is_some() {
+                return Err(eyre::eyre!("--bbox and --bbox-geo are mutually exclusive"));
+            }
+
+            if output.is_some() && output_name.is_some() {
+                return Err(eyre::eyre!("--output and --output-name are mutually exclusive"));
+            }
+
+            if output.is_some() && input_dtm.is_dir() {
+                return Err(eyre::eyre!("--output can't be used with a directory (batch) input_dtm"));
+            }
+
+            if !mosaic_with.is_empty() && input_dtm.is_dir() {
+                return Err(eyre::eyre!("--mosaic-with can't be used with a directory (batch) input_dtm"));
+            }
+
+            // Composited up front, once, rather than inside `run_one` below --
+            // a mosaic combines specific named tiles into one output, so
+            // unlike `--target-srs` it can't be re-run per file in batch
+            // mode (already rejected above) and only ever needs doing once.
+            let (input_dtm, _mosaic_temp_guard): (PathBuf, TempFileGuard) = if mosaic_with.is_empty() {
+                (input_dtm, TempFileGuard(None))
+            } else {
+                let mut tiles = vec![input_dtm];
+                tiles.extend(mosaic_with);
+                let mosaicked = mosaic_to_temp_geotiff(&tiles)?;
+                (mosaicked.clone(), TempFileGuard(Some(mosaicked)))
+            };
+
+            // `--output` bypasses `--output-dir`/`--output-name` entirely
+            // rather than threading a third path-shaped option through every
+            // export function; its parent directory takes the place of
+            // `output_dir` and its file stem the place of `output_name`.
+            let (output_dir, output_name): (PathBuf, Option<String>) = match &output {
+                Some(path) => {
+                    let parent = match path.parent() {
+                        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                        _ => PathBuf::from("."),
+                    };
+                    std::fs::create_dir_all(&parent)?;
+                    let name = path
+                        .file_stem()
+                        .ok_or_else(|| eyre::eyre!("--output {} has no file name", path.display()))?
+                        .to_string_lossy()
+                        .into_owned();
+                    (parent, Some(name))
+                }
+                None => (output_dir, output_name),
+            };
+
+            if should_validate_input {
+                validate_input(&input_dtm)?;
+            }
+
+            // Shared by the single-file path below and the directory-batch
+            // path: dispatches one input raster to whichever export function
+            // the flags ask for and hands back its output path. Each call
+            // gets its own progress bar so a batch's bars don't stomp on
+            // each other.
+            let run_one = |input_path: &PathBuf| -> Result<PathBuf> {
+                let reprojected = match &target_srs {
+                    Some(srs) => {
+                        let options = ReprojectOptions { target_resolution, target_extent };
+                        reproject_to_temp_geotiff(input_path, srs, &options)?
+                    }
+                    None => None,
+                };
+                let _temp_guard = TempFileGuard(reprojected.clone());
+                let input_path: &PathBuf = reprojected.as_ref().unwrap_or(input_path);
+
+                let bbox = match bbox_geo {
+                    Some(geo_bbox) => {
+                        let geo_transform = Dataset::open(input_path)?.geo_transform()?;
+                        Some(geo_bbox_to_pixel_bbox(&geo_transform, geo_bbox).map_err(|err| eyre::eyre!(err))?)
+                    }
+                    None => bbox,
+                };
+
+                let window_scale_factor = if auto_window {
+                    let dataset = Dataset::open(input_path)?;
+                    let (raster_w, raster_h) = dataset.raster_size();
+                    let factor = gdal_dtm_exporter_lib::suggest_window_scale_factor(
+                        raster_w,
+                        raster_h,
+                        dataset.raster_count(),
+                        memory_budget,
+                    );
+                    log::info!(
+                        "--auto-window picked --window-scale-factor {factor} for a {memory_budget}-byte budget ({raster_w}x{raster_h}, {} band(s))",
+                        dataset.raster_count()
+                    );
+                    factor
+                } else {
+                    window_scale_factor
+                };
+
+                if (bbox.is_some() || bbox_geo.is_some()) && (multiband_tiff || png || raw_mmap || exr_package) {
+                    eprintln!("warning: --bbox/--bbox-geo is only honored by the default EXR/GeoTIFF/PNG16 export path; ignoring it for this output format");
+                }
+
+                if output_name.is_some() && (multiband_tiff || png || raw_mmap || exr_package) {
+                    eprintln!("warning: --output-name/--output is only honored by the default EXR/GeoTIFF/PNG16 export path; ignoring it for this output format");
+                }
+
+                if multiband_tiff {
+                    export_multiband_to_tiff(input_path, &output_dir, overwrite)
+                } else if png {
+                    export_dtm_to_png(
+                        input_path,
+                        &output_dir,
+                        window_scale_factor,
+                        overwrite,
+                        png_bit_depth,
+                        png_mode,
+                        colorspace,
+                    )
+                } else if raw_mmap {
+                    export_dtm_to_raw_mmap(
+                        input_path,
+                        &output_dir,
+                        window_scale_factor,
+                        overwrite,
+                        erode,
+                        talus_angle,
+                    )
+                } else if exr_package {
+                    export_dtm_to_exr_package(input_path, &output_dir, overwrite)
+                } else {
+                    let total_pixels = match bbox {
+                        Some((min_x, min_y, max_x, max_y)) => ((max_x - min_x) * (max_y - min_y)) as u64,
+                        None => {
+                            let (w, h) = Dataset::open(input_path)?.raster_size();
+                            (w * h) as u64
+                        }
+                    };
+
+                    let show_progress = !quiet && !json_logs && std::io::stderr().is_terminal();
+                    let progress_bar = if show_progress {
+                        let bar = indicatif::ProgressBar::new(total_pixels);
+                        bar.set_style(
+                            indicatif::ProgressStyle::with_template(
+                                "[{elapsed_precise}] {bar:40.cyan/blue} {percent:>3}% ({mpix_per_sec}, eta: {eta})",
+                            )
+                            .unwrap()
+                            .with_key(
+                                "mpix_per_sec",
+                                |state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| {
+                                    let _ = write!(w, "{:.2} MPix/s", state.per_sec() / 1_000_000.0);
+                                },
+                            ),
+                        );
+                        bar
+                    } else {
+                        indicatif::ProgressBar::hidden()
+                    };
+                    let result = export_dtm_to_exr(
+                        input_path,
+                        &output_dir,
+                        output_name.as_deref(),
+                        window_scale_factor,
+                        bbox,
+                        normalize,
+                        overwrite,
+                        flip_y,
+                        flip_x,
+                        normalize_per_tile,
+                        manual_range,
+                        normalize_percentile,
+                        vertical_exaggeration,
+                        nodata_color,
+                        nodata_as,
+                        write_aux_xml,
+                        world_file,
+                        stats_json,
+                        colorspace,
+                        tonemap,
+                        gamma,
+                        graticule,
+                        graticule_color,
+                        graticule_opacity,
+                        use_metadata_offset,
+                        resample,
+                        tile_overlap,
+                        fill_voids,
+                        band_selection,
+                        format,
+                        exr_compression,
+                        channels,
+                        build_overviews,
+                        overview_resample_name(overview_resample),
+                        exr_tiled,
+                        exr_tile_size,
+                        mipmaps,
+                        with_mask,
+                        colormap,
+                        None,
+                        threads,
+                        Some(&|fraction| {
+                            if json_logs {
+                                log_progress_json(fraction);
+                            } else {
+                                progress_bar.set_position((fraction as f64 * total_pixels as f64) as u64);
+                            }
+                        }),
+                    );
+                    progress_bar.finish_and_clear();
+                    result
+                }
+            };
+
+            if input_dtm.is_dir() {
+                // Quantization/size-split reporting are single-file
+                // diagnostics opted into per export -- printing them for
+                // every file in a batch would mostly be noise, so batch
+                // mode skips straight to the summary instead.
+                let mut inputs: Vec<PathBuf> = std::fs::read_dir(&input_dtm)?
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| is_raster_like(path))
+                    .collect();
+                inputs.sort();
+
+                let mut succeeded = 0usize;
+                let mut failed = 0usize;
+                for input_path in &inputs {
+                    match run_one(input_path) {
+                        Ok(output_path) => {
+                            succeeded += 1;
+                            println!("Exported {} to {}", input_path.display(), output_path.display());
+                        }
+                        Err(err) => {
+                            failed += 1;
+                            eprintln!("failed to export {}: {err}", input_path.display());
+                            if !continue_on_error {
+                                return Err(err);
+                            }
+                        }
+                    }
+                }
+
+                println!("Batch complete: {succeeded} succeeded, {failed} failed out of {}", inputs.len());
+                return Ok(());
+            }
+
+            let output_path = run_one(&input_dtm)?;
+            println!("Exported to {}", output_path.display());
+
+            if let Some(output_bits) = report_quantization {
+                let dataset = Dataset::open(&input_dtm)?;
+                let (raster_w, raster_h) = dataset.raster_size();
+                let band = dataset.rasterband(1)?;
+                let buffer = band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+
+                let report = quantization_report(buffer.data(), output_bits);
+                println!(
+                    "Quantization at {}-bit: {:.2}% of source range representable per step, {} value(s) collapsed",
+                    output_bits,
+                    report.representable_fraction * 100.0,
+                    report.collapsed_value_count
+                );
+            }
+
+            if let Some(max_bytes) = split_output_by_size {
+                let actual_bytes = std::fs::metadata(&output_path)?.len();
+                if actual_bytes > max_bytes {
+                    let (tile_cols, tile_rows, entries) =
+                        plan_size_split_tiles(&input_dtm, max_bytes)?;
+                    println!(
+                        "Output ({actual_bytes} bytes) exceeds the {max_bytes}-byte limit; a {tile_cols}x{tile_rows} tile grid would keep each piece under it \
+                         (diagnostics only -- no tile files were written; re-export each footprint below with --bbox-geo to split by hand):"
+                    );
+                    for entry in entries {
+                        println!(
+                            "  {} bounds=({:.2}, {:.2})-({:.2}, {:.2})",
+                            entry.file_name, entry.min_x, entry.min_y, entry.max_x, entry.max_y
+                        );
+                    }
+                } else {
+                    println!("Output ({actual_bytes} bytes) is already under the {max_bytes}-byte limit; no split needed");
+                }
+            }
+        }
+
+        Command::Pack {
+            red,
+            green,
+            blue,
+            output_dir,
+            output_name,
+            overwrite,
+        } => {
+            let output_path =
+                pack_channels_to_exr(&red, &green, &blue, &output_dir, &output_name, overwrite)?;
+            println!("Packed to {}", output_path.display());
+        }
+
+        Command::Ply {
+            input_dtm,
+            output_dir,
+            y_scale,
+            window_scale_factor,
+            overwrite,
+            mesh_format,
+            ascii,
+            ply_colors,
+            with_uvs,
+            decimate,
+            max_faces,
+            downsample_method,
+            weld_tolerance,
+            decimate_adaptive,
+            target_vertices,
+            flip_y,
+        } => {
+            if decimate.is_some() && max_faces.is_some() {
+                return Err(eyre::eyre!("--decimate and --max-faces are mutually exclusive"));
+            }
+            if decimate_adaptive && target_vertices.is_none() {
+                return Err(eyre::eyre!("--decimate-adaptive requires --target-vertices"));
+            }
+            if target_vertices.is_some() && !decimate_adaptive {
+                return Err(eyre::eyre!("--target-vertices requires --decimate-adaptive"));
+            }
+            if decimate_adaptive && (decimate.is_some() || max_faces.is_some()) {
+                return Err(eyre::eyre!("--decimate-adaptive is mutually exclusive with --decimate/--max-faces"));
+            }
+            if decimate_adaptive && with_uvs {
+                return Err(eyre::eyre!("--decimate-adaptive is not supported together with --with-uvs"));
+            }
+
+            let decimate = match max_faces {
+                Some(max_faces) => {
+                    let (raster_w, raster_h) = Dataset::open(&input_dtm)?.raster_size();
+                    decimation_stride_for_max_faces(raster_w, raster_h, max_faces)
+                }
+                None => decimate.unwrap_or(1),
+            };
+            let target_vertices = if decimate_adaptive { target_vertices } else { None };
+
+            let output_path = match mesh_format {
+                MeshFormat::Ply => {
+                    let format = if ascii { PlyFormat::Ascii } else { PlyFormat::BinaryLittleEndian };
+                    export_dtm_to_ply(
+                        &input_dtm, &output_dir, y_scale, window_scale_factor, overwrite, format, ply_colors,
+                        decimate, downsample_method, weld_tolerance, target_vertices, flip_y,
+                    )?
+                }
+                MeshFormat::Obj => {
+                    export_dtm_to_obj(
+                        &input_dtm, &output_dir, y_scale, window_scale_factor, overwrite, with_uvs, decimate,
+                        downsample_method, weld_tolerance, target_vertices, flip_y,
+                    )?
+                }
+                MeshFormat::Usda => {
+                    export_dtm_to_usda(
+                        &input_dtm, &output_dir, y_scale, window_scale_factor, overwrite, decimate,
+                        downsample_method, weld_tolerance, target_vertices, flip_y,
+                    )?
+                }
+            };
+            println!("Exported to {}", output_path.display());
+        }
+
+        Command::Contours {
+            input_dtm,
+            output_dir,
+            contours,
+            contour_base,
+            shapefile,
+        } => {
+            let output_path = export_contours(&input_dtm, &output_dir, contours, contour_base, shapefile)?;
+            println!("Exported to {}", output_path.display());
+        }
+
+        Command::Hillshade {
+            input_dtm,
+            output_dir,
+            overwrite,
+            azimuth,
+            altitude,
+            z_factor,
+            format,
+        } => {
+            let output_path = export_hillshade(&input_dtm, &output_dir, overwrite, azimuth, altitude, z_factor, format)?;
+            println!("Exported to {}", output_path.display());
+        }
+
+        Command::Slope {
+            input_dtm,
+            output_dir,
+            overwrite,
+            slope_units,
+            format,
+        } => {
+            let output_path = export_slope(&input_dtm, &output_dir, overwrite, slope_units, format)?;
+            println!("Exported to {}", output_path.display());
+        }
+
+        Command::Aspect {
+            input_dtm,
+            output_dir,
+            overwrite,
+            format,
+        } => {
+            let output_path = export_aspect(&input_dtm, &output_dir, overwrite, format)?;
+            println!("Exported to {}", output_path.display());
+        }
+
+        Command::ListFormats { json } => {
+            let formats = list_formats();
+
+            if json {
+                let entries: Vec<_> = formats
+                    .iter()
+                    .map(|f| {
+                        serde_json::json!({
+                            "format": format!("{:?}", f.format),
+                            "extension": f.extension,
+                            "preserves_georeferencing": f.preserves_georeferencing,
+                            "description": f.description,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            } else {
+                for f in formats {
+                    println!(
+                        "{:?}\t.{}\tgeoref={}\t{}",
+                        f.format, f.extension, f.preserves_georeferencing, f.description
+                    );
+                }
+            }
+        }
+
+        Command::Info { input_dtm, json, histogram, histogram_out } => {
+            let info = inspect_dtm(&input_dtm)?;
+
+            if histogram_out.is_some() && histogram.is_none() {
+                return Err(eyre::eyre!("--histogram-out requires --histogram"));
+            }
+
+            if json {
+                let bands: Vec<_> = info
+                    .bands
+                    .iter()
+                    .map(|b| {
+                        serde_json::json!({
+                            "min": b.min,
+                            "max": b.max,
+                            "no_data_value": b.no_data_value,
+                            "data_type": b.data_type,
+                        })
+                    })
+                    .collect();
+                let report = serde_json::json!({
+                    "driver": info.driver_name,
+                    "crs": info.crs_name,
+                    "width": info.raster_width,
+                    "height": info.raster_height,
+                    "bands": bands,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("Driver: {}", info.driver_name);
+                println!("CRS: {}", info.crs_name);
+                println!("Size: {}x{}, {} band(s)", info.raster_width, info.raster_height, info.bands.len());
+                for (index, band) in info.bands.iter().enumerate() {
+                    let no_data = band
+                        .no_data_value
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "none".to_string());
+                    println!(
+                        "  band {}: {:.2} .. {:.2}, no-data={}, type={}",
+                        index + 1,
+                        band.min,
+                        band.max,
+                        no_data,
+                        band.data_type
+                    );
+                }
+            }
+
+            if let Some(bins) = histogram {
+                let histogram = elevation_histogram(&input_dtm, 1, bins)?;
+                match &histogram_out {
+                    Some(path) => write_histogram_file(&histogram, path)?,
+                    None => print_histogram_chart(&histogram),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders `histogram` as a fixed-width ASCII bar chart, one line per
+/// bucket, scaled so the fullest bucket's bar spans the full width.
+fn print_histogram_chart(histogram: &gdal_dtm_exporter_lib::Histogram) {
+    const BAR_WIDTH: usize = 40;
+
+    let max_count = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+    for (index, &count) in histogram.counts.iter().enumerate() {
+        let low = histogram.min + index as f64 * histogram.bin_width;
+        let high = low + histogram.bin_width;
+        let bar_len = ((count as f64 / max_count as f64) * BAR_WIDTH as f64).round() as usize;
+        println!("{low:>10.2} .. {high:>10.2} | {} {count}", "#".repeat(bar_len));
+    }
+}
+
+/// Writes `histogram` to `path`, as CSV if the extension is `.csv` and as
+/// JSON otherwise.
+fn write_histogram_file(histogram: &gdal_dtm_exporter_lib::Histogram, path: &PathBuf) -> Result<()> {
+    let is_csv = path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.eq_ignore_ascii_case("csv")).unwrap_or(false);
+
+    if is_csv {
+        let mut csv = String::from("bin_low,bin_high,count\n");
+        for (index, &count) in histogram.counts.iter().enumerate() {
+            let low = histogram.min + index as f64 * histogram.bin_width;
+            let high = low + histogram.bin_width;
+            csv.push_str(&format!("{low},{high},{count}\n"));
+        }
+        std::fs::write(path, csv)?;
+    } else {
+        let buckets: Vec<_> = histogram
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(index, &count)| {
+                let low = histogram.min + index as f64 * histogram.bin_width;
+                let high = low + histogram.bin_width;
+                serde_json::json!({ "low": low, "high": high, "count": count })
+            })
+            .collect();
+        let report = serde_json::json!({ "min": histogram.min, "max": histogram.max, "buckets": buckets });
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+    }
+
+    log::info!("wrote histogram to {}", path.display());
+    Ok(())
+}
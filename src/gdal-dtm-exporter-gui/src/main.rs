@@ -0,0 +1,609 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+use eframe::egui;
+use gdal::raster::ResampleAlg;
+use gdal_dtm_exporter_lib::{
+    build_preview_grid, describe_dataset, export_dtm_to_exr, export_dtm_to_ply, list_rasters_in_zip,
+    BandSelection, DatasetInfo, DtmOutputFormat, NodataAs, PlyFormat, PreviewGrid,
+};
+
+const OUTPUT_FORMAT_OPTIONS: &[(DtmOutputFormat, &str)] = &[
+    (DtmOutputFormat::Exr, "EXR"),
+    (DtmOutputFormat::GeoTiff, "GeoTIFF"),
+    (DtmOutputFormat::Png16, "PNG (16-bit)"),
+];
+
+const RESAMPLE_OPTIONS: &[(ResampleAlg, &str)] = &[
+    (ResampleAlg::NearestNeighbour, "nearest"),
+    (ResampleAlg::Bilinear, "bilinear"),
+    (ResampleAlg::Cubic, "cubic"),
+    (ResampleAlg::Average, "average"),
+    (ResampleAlg::Lanczos, "lanczos"),
+];
+
+fn resample_label(resample: ResampleAlg) -> &'static str {
+    match resample {
+        ResampleAlg::NearestNeighbour => "nearest",
+        ResampleAlg::Bilinear => "bilinear",
+        ResampleAlg::Cubic => "cubic",
+        ResampleAlg::Average => "average",
+        ResampleAlg::Lanczos => "lanczos",
+        _ => "bilinear",
+    }
+}
+
+/// Why `input_file` can't be exported right now, if anything -- used both to
+/// disable the export button and to explain the disabling inline, instead of
+/// letting a stale or unsupported path reach the worker thread and fail
+/// there after the fact.
+///
+/// Skips the filesystem-existence check for GDAL virtual filesystem paths
+/// (`/vsi...`, as picked from a `.zip` archive's entry list), the same way
+/// [`gdal_dtm_exporter_lib::error::DtmExportError::InputNotFound`] does --
+/// those aren't checkable with [`std::path::Path::exists`].
+fn input_file_issue(input_file: &Option<PathBuf>) -> Option<String> {
+    let path = input_file.as_ref()?;
+
+    let is_raster_like = matches!(
+        path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()),
+        Some(ext) if matches!(ext.as_str(), "tif" | "tiff" | "img" | "dem" | "hgt" | "asc" | "vrt")
+    );
+    if !is_raster_like {
+        return Some(format!("unsupported file extension: {}", path.display()));
+    }
+
+    let is_vsi_path = path.to_str().map(|s| s.starts_with("/vsi")).unwrap_or(false);
+    if !is_vsi_path && !path.exists() {
+        return Some(format!("input file does not exist: {}", path.display()));
+    }
+
+    None
+}
+
+/// Rough estimate of the exported file's size in bytes, for display before
+/// committing to an export -- not exact, since OpenEXR's lossless ZIP
+/// compression ratio depends on how smooth the terrain actually is.
+fn estimate_output_size_bytes(
+    format: DtmOutputFormat,
+    width: usize,
+    height: usize,
+    band_count: usize,
+    with_mask: bool,
+) -> u64 {
+    let pixels = (width * height) as u64;
+    match format {
+        DtmOutputFormat::Exr => {
+            let channels = if with_mask { 4 } else { 3 };
+            let uncompressed = pixels * channels as u64 * 4;
+            // ZIP16 (this app's hardcoded EXR compression) on smooth,
+            // low-entropy elevation data typically lands around half the
+            // uncompressed size -- a rough estimate, but a far better one
+            // than implying the uncompressed size is what'll land on disk.
+            uncompressed / 2
+        }
+        DtmOutputFormat::GeoTiff => pixels * band_count.max(1) as u64 * 4,
+        DtmOutputFormat::Png16 => pixels * 2,
+    }
+}
+
+/// Formats `bytes` as a human-readable size (B/KB/MB/GB), for
+/// [`estimate_output_size_bytes`]'s display.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = UNITS[0];
+    for &next_unit in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{value:.1} {unit}")
+}
+
+/// Largest dimension of the in-memory preview grid; keeps the panel
+/// responsive regardless of the source raster's actual resolution.
+const PREVIEW_MAX_DIMENSION: usize = 512;
+
+/// `eframe::Storage` key [`MyApp`]'s persisted settings are saved/restored
+/// under.
+const SETTINGS_STORAGE_KEY: &str = "gdal-dtm-exporter-settings";
+
+/// The subset of [`MyApp`]'s fields worth remembering between launches --
+/// the ones most tediously re-entered every session. Everything else
+/// (input file, conversion state, preview) is inherently per-session and
+/// stays at its [`MyApp::default`] value on restart.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PersistedSettings {
+    output_dir: PathBuf,
+    normalize: bool,
+    overwrite_output: bool,
+    window_scale_factor: usize,
+}
+
+enum ConversionStatus {
+    NotStarted,
+    InProgress,
+}
+
+/// Sent from the export worker thread back to the UI thread. `Progress` can
+/// fire many times; `Done` fires exactly once and ends the conversion.
+enum ConversionEvent {
+    Progress(f32),
+    Done(Result<PathBuf, String>),
+}
+
+struct MyApp {
+    input_file: Option<PathBuf>,
+    output_dir: PathBuf,
+    window_scale_factor: usize,
+    auto_window: bool,
+    normalize: bool,
+    overwrite_output: bool,
+    flip_y: bool,
+    flip_x: bool,
+    resample: ResampleAlg,
+    output_format: DtmOutputFormat,
+    world_file: bool,
+    with_mask: bool,
+    export_as_ply: bool,
+
+    status: ConversionStatus,
+    conversion_rx: Option<Receiver<ConversionEvent>>,
+    progress_fraction: f32,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    /// Outcome of the most recent conversion, kept around so it stays
+    /// visible until the next one starts instead of disappearing the moment
+    /// `status` flips back to `NotStarted`.
+    last_result: Option<Result<PathBuf, String>>,
+
+    dataset_info: Option<DatasetInfo>,
+    dataset_info_rx: Option<Receiver<eyre::Result<DatasetInfo>>>,
+
+    /// Rasters found inside a picked `.zip` archive, offered as a selection
+    /// list before one is turned into a `/vsizip/` input path.
+    zip_entries: Option<Vec<String>>,
+
+    preview_grid: Option<PreviewGrid>,
+    preview_rx: Option<Receiver<eyre::Result<PreviewGrid>>>,
+    preview_texture: Option<egui::TextureHandle>,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            input_file: None,
+            output_dir: PathBuf::from("/tmp"),
+            window_scale_factor: 1,
+            auto_window: false,
+            normalize: true,
+            overwrite_output: false,
+            flip_y: false,
+            flip_x: false,
+            resample: ResampleAlg::Bilinear,
+            output_format: DtmOutputFormat::Exr,
+            world_file: false,
+            with_mask: false,
+            export_as_ply: false,
+            status: ConversionStatus::NotStarted,
+            conversion_rx: None,
+            progress_fraction: 0.0,
+            cancel_flag: None,
+            last_result: None,
+            dataset_info: None,
+            dataset_info_rx: None,
+            zip_entries: None,
+            preview_grid: None,
+            preview_rx: None,
+            preview_texture: None,
+        }
+    }
+}
+
+/// Maps `grid`'s elevation values onto a grayscale `egui::ColorImage`.
+fn preview_to_color_image(grid: &PreviewGrid) -> egui::ColorImage {
+    let range = (grid.elevation_max - grid.elevation_min).max(f32::EPSILON);
+
+    let pixels: Vec<egui::Color32> = grid
+        .values
+        .iter()
+        .map(|&v| {
+            let t = ((v - grid.elevation_min) / range).clamp(0.0, 1.0);
+            let gray = (t * 255.0) as u8;
+            egui::Color32::from_gray(gray)
+        })
+        .collect();
+
+    egui::ColorImage {
+        size: [grid.width, grid.height],
+        pixels,
+    }
+}
+
+impl MyApp {
+    /// Builds the app, restoring [`PersistedSettings`] from `cc`'s storage
+    /// (if any was saved by a previous run) on top of the usual defaults.
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+
+        if let Some(storage) = cc.storage {
+            if let Some(settings) = eframe::get_value::<PersistedSettings>(storage, SETTINGS_STORAGE_KEY) {
+                app.output_dir = settings.output_dir;
+                app.normalize = settings.normalize;
+                app.overwrite_output = settings.overwrite_output;
+                app.window_scale_factor = settings.window_scale_factor;
+            }
+        }
+
+        app
+    }
+
+    /// Kicks off the background reads (dataset info + preview grid) for a
+    /// newly picked input path, clearing whatever was shown before.
+    fn load_file(&mut self, path: PathBuf) {
+        self.zip_entries = None;
+        self.input_file = Some(path.clone());
+        self.dataset_info = None;
+        self.preview_grid = None;
+        self.preview_texture = None;
+
+        let (info_tx, info_rx) = std::sync::mpsc::channel();
+        self.dataset_info_rx = Some(info_rx);
+
+        let (preview_tx, preview_rx) = std::sync::mpsc::channel();
+        self.preview_rx = Some(preview_rx);
+
+        std::thread::spawn(move || {
+            info_tx.send(describe_dataset(&path)).ok();
+            preview_tx.send(build_preview_grid(&path, PREVIEW_MAX_DIMENSION)).ok();
+        });
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let mut finished = false;
+        if let Some(rx) = &self.conversion_rx {
+            while let Ok(event) = rx.try_recv() {
+                match event {
+                    ConversionEvent::Progress(fraction) => self.progress_fraction = fraction,
+                    ConversionEvent::Done(result) => {
+                        self.last_result = Some(result);
+                        finished = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if finished {
+            self.status = ConversionStatus::NotStarted;
+            self.conversion_rx = None;
+            self.cancel_flag = None;
+        }
+
+        if let Some(rx) = &self.dataset_info_rx {
+            if let Ok(result) = rx.try_recv() {
+                match result {
+                    Ok(info) => self.dataset_info = Some(info),
+                    Err(err) => log::error!("failed to describe dataset: {}", err),
+                }
+                self.dataset_info_rx = None;
+            }
+        }
+
+        if let Some(rx) = &self.preview_rx {
+            if let Ok(result) = rx.try_recv() {
+                match result {
+                    Ok(grid) => {
+                        let texture = ctx.load_texture(
+                            "dtm-preview",
+                            preview_to_color_image(&grid),
+                            egui::TextureOptions::NEAREST,
+                        );
+                        self.preview_texture = Some(texture);
+                        self.preview_grid = Some(grid);
+                    }
+                    Err(err) => log::error!("failed to build preview: {}", err),
+                }
+                self.preview_rx = None;
+            }
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("DTM to OpenEXR");
+
+            ui.horizontal(|ui| {
+                if ui.button("Pick DTM file..").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        self.load_file(path);
+                    }
+                }
+
+                if ui.button("Pick from .zip archive..").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().add_filter("zip", &["zip"]).pick_file() {
+                        match list_rasters_in_zip(&path) {
+                            Ok(entries) => self.zip_entries = Some(entries),
+                            Err(err) => log::error!("failed to list archive {}: {}", path.display(), err),
+                        }
+                    }
+                }
+            });
+
+            if let Some(entries) = self.zip_entries.clone() {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.label("Rasters in archive:");
+                    for entry in &entries {
+                        if ui.button(entry).clicked() {
+                            self.load_file(PathBuf::from(entry));
+                        }
+                    }
+                });
+            }
+
+            if let Some(input_file) = &self.input_file {
+                ui.label(format!("Input: {}", input_file.display()));
+            }
+
+            if let Some(info) = &self.dataset_info {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.label(format!("CRS: {}", info.crs_name));
+                    ui.label(format!(
+                        "Size: {}x{}, {} band(s)",
+                        info.raster_width, info.raster_height, info.band_count
+                    ));
+                    ui.label(format!(
+                        "Elevation: {:.2} .. {:.2}",
+                        info.elevation_min, info.elevation_max
+                    ));
+                    let estimated_bytes = estimate_output_size_bytes(
+                        self.output_format,
+                        info.raster_width,
+                        info.raster_height,
+                        info.band_count,
+                        self.with_mask,
+                    );
+                    ui.label(format!("Estimated output size: ~{}", format_bytes(estimated_bytes)));
+                });
+            }
+
+            if self.preview_rx.is_some() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Building preview...");
+                });
+            }
+
+            if let (Some(texture), Some(grid)) = (&self.preview_texture, &self.preview_grid) {
+                egui::CollapsingHeader::new("Preview").default_open(true).show(ui, |ui| {
+                    let size = texture.size_vec2();
+                    let response = egui::ScrollArea::both()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            ui.add(egui::Image::new(texture, size).sense(egui::Sense::hover()))
+                        })
+                        .inner;
+
+                    if let Some(hover_pos) = response.hover_pos() {
+                        let local = hover_pos - response.rect.min;
+                        let preview_x = (local.x / response.rect.width() * grid.width as f32)
+                            .clamp(0.0, grid.width as f32 - 1.0) as usize;
+                        let preview_y = (local.y / response.rect.height() * grid.height as f32)
+                            .clamp(0.0, grid.height as f32 - 1.0) as usize;
+
+                        let elevation = grid.values[preview_y * grid.width + preview_x];
+                        let (source_x, source_y) = grid.preview_to_source_pixel(preview_x, preview_y);
+                        let (world_x, world_y) = grid.source_pixel_to_world(source_x, source_y);
+
+                        egui::show_tooltip(ui.ctx(), egui::Id::new("preview-readout"), |ui| {
+                            ui.label(format!("pixel: ({:.0}, {:.0})", source_x, source_y));
+                            ui.label(format!("world: ({:.2}, {:.2})", world_x, world_y));
+                            ui.label(format!("elevation: {:.2}", elevation));
+                        });
+                    }
+                });
+            }
+
+            egui::CollapsingHeader::new("Conversion Options").show(ui, |ui| {
+                ui.add_enabled(
+                    !self.auto_window,
+                    egui::Slider::new(&mut self.window_scale_factor, 1..=64).text("window scale factor"),
+                );
+                ui.checkbox(&mut self.auto_window, "Auto (pick from available memory)");
+                ui.checkbox(&mut self.normalize, "normalize");
+                ui.checkbox(&mut self.overwrite_output, "overwrite output");
+                ui.checkbox(&mut self.flip_y, "flip y");
+                ui.checkbox(&mut self.flip_x, "flip x");
+                ui.checkbox(&mut self.world_file, "write world file sidecar");
+
+                egui::ComboBox::from_label("resample")
+                    .selected_text(resample_label(self.resample))
+                    .show_ui(ui, |ui| {
+                        for &(option, label) in RESAMPLE_OPTIONS {
+                            if ui.selectable_label(resample_label(self.resample) == label, label).clicked() {
+                                self.resample = option;
+                            }
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label("output format:");
+                    for &(option, label) in OUTPUT_FORMAT_OPTIONS {
+                        ui.radio_value(&mut self.output_format, option, label);
+                    }
+                });
+
+                ui.checkbox(&mut self.with_mask, "add alpha/mask channel (EXR only)");
+                ui.checkbox(&mut self.export_as_ply, "export as PLY mesh instead");
+            });
+
+            match self.status {
+                ConversionStatus::NotStarted => {
+                    let input_issue = input_file_issue(&self.input_file);
+
+                    if ui
+                        .add_enabled(input_issue.is_none(), egui::Button::new("Export to OpenEXR"))
+                        .clicked()
+                    {
+                        if let Some(input_file) = self.input_file.clone() {
+                            let output_dir = self.output_dir.clone();
+                            let window_scale_factor = match (self.auto_window, &self.dataset_info) {
+                                (true, Some(info)) => {
+                                    let factor = gdal_dtm_exporter_lib::suggest_window_scale_factor(
+                                        info.raster_width,
+                                        info.raster_height,
+                                        info.band_count,
+                                        gdal_dtm_exporter_lib::DEFAULT_MEMORY_BUDGET_BYTES,
+                                    );
+                                    log::info!("auto window scale factor: {factor}");
+                                    factor
+                                }
+                                _ => self.window_scale_factor,
+                            };
+                            let normalize = self.normalize;
+                            let overwrite = self.overwrite_output;
+                            let flip_y = self.flip_y;
+                            let flip_x = self.flip_x;
+                            let resample = self.resample;
+                            let output_format = self.output_format;
+                            let world_file = self.world_file;
+                            let with_mask = self.with_mask;
+                            let export_as_ply = self.export_as_ply;
+
+                            let (conversion_tx, conversion_rx) = std::sync::mpsc::channel();
+                            self.conversion_rx = Some(conversion_rx);
+                            self.progress_fraction = 0.0;
+                            self.last_result = None;
+
+                            let cancel_flag = Arc::new(AtomicBool::new(false));
+                            self.cancel_flag = Some(cancel_flag.clone());
+
+                            self.status = ConversionStatus::InProgress;
+
+                            std::thread::spawn(move || {
+                                let result = if export_as_ply {
+                                    export_dtm_to_ply(
+                                        &input_file,
+                                        &output_dir,
+                                        1.0,
+                                        window_scale_factor,
+                                        overwrite,
+                                        PlyFormat::BinaryLittleEndian,
+                                        None, // ply_colors (not exposed in the GUI yet)
+                                        1,    // decimate (not exposed in the GUI yet)
+                                        gdal_dtm_exporter_lib::filters::DownsampleMethod::Average,
+                                        None, // weld_tolerance (not exposed in the GUI yet)
+                                        None, // target_vertices (not exposed in the GUI yet)
+                                        flip_y,
+                                    )
+                                } else {
+                                    export_dtm_to_exr(
+                                        &input_file,
+                                        &output_dir,
+                                        None, // output_name (not exposed in the GUI yet)
+                                        window_scale_factor,
+                                        None, // bbox (not exposed in the GUI yet)
+                                        normalize,
+                                        overwrite,
+                                        flip_y,
+                                        flip_x,
+                                        false,
+                                        None,
+                                        None,
+                                        1.0,
+                                        None,
+                                        NodataAs::Zero,
+                                        false,
+                                        world_file,
+                                        false,
+                                        gdal_dtm_exporter_lib::formats::ColorSpace::Linear,
+                                        gdal_dtm_exporter_lib::formats::Tonemap::Linear, // tonemap (not exposed in the GUI yet)
+                                        2.2, // gamma (not exposed in the GUI yet)
+                                        None,
+                                        [1.0, 0.0, 0.0],
+                                        0.5,
+                                        false,
+                                        resample,
+                                        0, // tile_overlap (not exposed in the GUI yet)
+                                        None, // fill_voids (not exposed in the GUI yet)
+                                        BandSelection::All,
+                                        output_format,
+                                        gdal_dtm_exporter_lib::formats::ExrCompression::Zip,
+                                        gdal_dtm_exporter_lib::formats::ExrChannels::Rgb,
+                                        false, // build_overviews (not exposed in the GUI yet)
+                                        "AVERAGE", // overview_resample (not exposed in the GUI yet)
+                                        false, // exr_tiled (not exposed in the GUI yet)
+                                        (128, 128), // exr_tile_size (not exposed in the GUI yet)
+                                        false, // mipmaps (not exposed in the GUI yet)
+                                        with_mask,
+                                        None,
+                                        Some(cancel_flag),
+                                        None,
+                                        Some(&|fraction| {
+                                            conversion_tx.send(ConversionEvent::Progress(fraction)).ok();
+                                        }),
+                                    )
+                                };
+                                if let Err(err) = &result {
+                                    log::error!("conversion failed: {}", err);
+                                }
+                                conversion_tx
+                                    .send(ConversionEvent::Done(result.map_err(|err| err.to_string())))
+                                    .ok();
+                            });
+                        }
+                    }
+
+                    if let Some(issue) = &input_issue {
+                        ui.colored_label(egui::Color32::RED, issue);
+                    }
+                }
+                ConversionStatus::InProgress => {
+                    ui.add(egui::ProgressBar::new(self.progress_fraction).show_percentage());
+                    if ui.button("Cancel").clicked() {
+                        if let Some(cancel_flag) = &self.cancel_flag {
+                            cancel_flag.store(true, Ordering::Relaxed);
+                        }
+                    }
+                }
+            }
+
+            match &self.last_result {
+                Some(Ok(output_path)) => {
+                    ui.label(format!("Exported to {}", output_path.display()));
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::RED, format!("Conversion failed: {message}"));
+                }
+                None => {}
+            }
+        });
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(
+            storage,
+            SETTINGS_STORAGE_KEY,
+            &PersistedSettings {
+                output_dir: self.output_dir.clone(),
+                normalize: self.normalize,
+                overwrite_output: self.overwrite_output,
+                window_scale_factor: self.window_scale_factor,
+            },
+        );
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    env_logger::init();
+
+    let options = eframe::NativeOptions::default();
+    eframe::run_native(
+        "gdal-dtm-exporter",
+        options,
+        Box::new(|cc| Box::new(MyApp::new(cc))),
+    )
+}
@@ -0,0 +1,86 @@
+//! Named colormaps for mapping a normalized `[0, 1]` elevation to RGB,
+//! instead of replicating the same value into all three channels.
+//!
+//! Each map is a handful of hand-picked color stops rather than a faithful
+//! reproduction of the matplotlib/Turbo originals -- good enough to tell
+//! "low" from "high" at a glance without carrying a few hundred sampled
+//! coefficients around for a DTM previewer.
+
+use image::Rgb;
+
+/// Which named colormap [`apply`] should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Turbo,
+    Terrain,
+}
+
+type Stop = (f32, [f32; 3]);
+
+const VIRIDIS: &[Stop] = &[
+    (0.0, [0.267, 0.005, 0.329]),
+    (0.25, [0.282, 0.141, 0.458]),
+    (0.5, [0.128, 0.567, 0.551]),
+    (0.75, [0.478, 0.821, 0.318]),
+    (1.0, [0.993, 0.906, 0.144]),
+];
+
+const MAGMA: &[Stop] = &[
+    (0.0, [0.001, 0.000, 0.016]),
+    (0.25, [0.317, 0.071, 0.485]),
+    (0.5, [0.716, 0.215, 0.475]),
+    (0.75, [0.957, 0.464, 0.367]),
+    (1.0, [0.987, 0.991, 0.749]),
+];
+
+const TURBO: &[Stop] = &[
+    (0.0, [0.190, 0.072, 0.232]),
+    (0.2, [0.271, 0.467, 0.991]),
+    (0.4, [0.164, 0.850, 0.736]),
+    (0.6, [0.686, 0.972, 0.229]),
+    (0.8, [0.980, 0.623, 0.144]),
+    (1.0, [0.480, 0.016, 0.011]),
+];
+
+const TERRAIN: &[Stop] = &[
+    (0.0, [0.2, 0.2, 0.6]),
+    (0.2, [0.0, 0.6, 0.8]),
+    (0.4, [0.0, 0.8, 0.4]),
+    (0.6, [0.8, 0.7, 0.4]),
+    (0.8, [0.6, 0.4, 0.2]),
+    (1.0, [1.0, 1.0, 1.0]),
+];
+
+fn lerp_stops(t: f32, stops: &[Stop]) -> Rgb<f32> {
+    let t = t.clamp(0.0, 1.0);
+
+    for (a, b) in stops.iter().zip(stops.iter().skip(1)) {
+        if t <= b.0 {
+            let span = (b.0 - a.0).max(f32::EPSILON);
+            let local_t = (t - a.0) / span;
+            return Rgb([
+                a.1[0] + (b.1[0] - a.1[0]) * local_t,
+                a.1[1] + (b.1[1] - a.1[1]) * local_t,
+                a.1[2] + (b.1[2] - a.1[2]) * local_t,
+            ]);
+        }
+    }
+
+    Rgb(stops[stops.len() - 1].1)
+}
+
+/// Maps a normalized elevation `t` (`[0, 1]`, clamped if out of range) to an
+/// RGB color via `map`'s color stops, linearly interpolated between the two
+/// stops `t` falls between.
+pub fn apply(map: Colormap, t: f32) -> Rgb<f32> {
+    let stops = match map {
+        Colormap::Viridis => VIRIDIS,
+        Colormap::Magma => MAGMA,
+        Colormap::Turbo => TURBO,
+        Colormap::Terrain => TERRAIN,
+    };
+
+    lerp_stops(t, stops)
+}
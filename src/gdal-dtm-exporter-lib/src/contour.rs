@@ -0,0 +1,106 @@
+//! Vector contour line extraction via GDAL's `GDALContourGenerate`.
+//!
+//! The `gdal` crate doesn't wrap this algorithm, so this module reaches
+//! past it into `gdal-sys` directly, borrowing the raw raster band and OGR
+//! layer handles `gdal` already manages for us rather than duplicating its
+//! dataset-opening/driver-lookup logic.
+
+use std::path::Path;
+
+use eyre::{eyre, Result};
+use gdal::vector::{LayerAccess, LayerOptions, OGRFieldType, OGRwkbGeometryType};
+use gdal::{Dataset, DriverManager};
+use log::info;
+
+/// Runs `GDALContourGenerate` over `in_image_path`'s first band and writes
+/// the resulting contour lines into `export_dir`, named after the input
+/// file's stem, as a GeoJSON (`.geojson`) or, with `as_shapefile`, an ESRI
+/// Shapefile (`.shp`) -- both in the source raster's CRS.
+///
+/// Lines are generated every `interval` elevation units, offset by `base`
+/// (so the first line sits at `base`, the next at `base + interval`, and so
+/// on); `base` defaults to `0.0` for a plain multiple-of-`interval` contour
+/// set. Pixels at the band's own `no_data_value`, if it has one, are
+/// excluded from contouring rather than treated as a real (and likely
+/// wildly out-of-range) elevation.
+///
+/// Each line feature carries an `ID` (a contour index, per
+/// `GDALContourGenerate`'s own sequencing) and an `elev` field with the
+/// elevation it was traced at.
+pub fn export_contours(
+    in_image_path: impl AsRef<Path>,
+    export_dir: impl AsRef<Path>,
+    interval: f64,
+    base: f64,
+    as_shapefile: bool,
+) -> Result<std::path::PathBuf> {
+    let in_image_path = in_image_path.as_ref();
+    let export_dir = export_dir.as_ref();
+
+    if interval <= 0.0 {
+        return Err(eyre!("--contours interval must be positive, got {interval}"));
+    }
+
+    let dataset = Dataset::open(in_image_path)?;
+    let band = dataset.rasterband(1)?;
+    let spatial_ref = dataset.spatial_ref().ok();
+
+    std::fs::create_dir_all(export_dir)?;
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let extension = if as_shapefile { "shp" } else { "geojson" };
+    let output_path = export_dir.join(stem).with_extension(extension);
+
+    if output_path.exists() {
+        std::fs::remove_file(&output_path)?;
+    }
+
+    let driver_name = if as_shapefile { "ESRI Shapefile" } else { "GeoJSON" };
+    let driver = DriverManager::get_driver_by_name(driver_name)?;
+    let mut vector_dataset = driver.create_vector_only(&output_path)?;
+    let mut layer = vector_dataset.create_layer(LayerOptions {
+        name: "contours",
+        srs: spatial_ref.as_ref(),
+        ty: OGRwkbGeometryType::wkbLineString,
+        ..Default::default()
+    })?;
+    layer.create_defn_fields(&[("ID", OGRFieldType::OFTInteger), ("elev", OGRFieldType::OFTReal)])?;
+
+    let (use_nodata, nodata_value) = match band.no_data_value() {
+        Some(value) => (1, value),
+        None => (0, 0.0),
+    };
+
+    let result = unsafe {
+        gdal_sys::GDALContourGenerate(
+            band.c_rasterband(),
+            interval,
+            base,
+            0,
+            std::ptr::null_mut(),
+            use_nodata,
+            nodata_value,
+            layer.c_layer() as *mut std::ffi::c_void,
+            0,
+            1,
+            None,
+            std::ptr::null_mut(),
+        )
+    };
+    drop(layer);
+    drop(vector_dataset);
+
+    if result != gdal_sys::CPLErr::CE_None {
+        std::fs::remove_file(&output_path).ok();
+        return Err(eyre!("GDALContourGenerate failed ({result:?})"));
+    }
+
+    info!(
+        "wrote contours at {interval} unit intervals (base {base}) from {} to {}",
+        in_image_path.display(),
+        output_path.display()
+    );
+
+    Ok(output_path)
+}
@@ -0,0 +1,45 @@
+//! Aesthetic (non-physical) terrain erosion pre-pass.
+
+/// Runs a simple thermal-erosion pass over `grid` in place: material above
+/// `talus_angle` (expressed as a height delta per cell) is redistributed to
+/// lower neighbors, smoothing out unrealistically steep slopes into the
+/// "eroded" look terrain artists often want.
+///
+/// This is purely an aesthetic transform; it does not model real sediment
+/// transport and is not physically accurate. Single-threaded by design — it
+/// runs a handful of times on already-small preview grids, not full-res DTMs.
+pub fn apply_thermal_erosion(grid: &mut [f32], width: usize, height: usize, iterations: usize, talus_angle: f32) {
+    for _ in 0..iterations {
+        let snapshot = grid.to_vec();
+
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let current = snapshot[i];
+
+                let mut neighbors = Vec::with_capacity(4);
+                if x > 0 {
+                    neighbors.push(i - 1);
+                }
+                if x + 1 < width {
+                    neighbors.push(i + 1);
+                }
+                if y > 0 {
+                    neighbors.push(i - width);
+                }
+                if y + 1 < height {
+                    neighbors.push(i + width);
+                }
+
+                for &n in &neighbors {
+                    let delta = current - snapshot[n];
+                    if delta > talus_angle {
+                        let transfer = (delta - talus_angle) * 0.25;
+                        grid[i] -= transfer;
+                        grid[n] += transfer;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Structured failure modes that are common enough for a caller to want to
+//! match on directly instead of inspecting an [`eyre::Report`]'s message.
+//!
+//! This isn't a wholesale replacement for `eyre` -- most of the crate still
+//! builds ad hoc [`eyre::eyre!`] errors for cases that are one-off or purely
+//! diagnostic, and that's fine. [`DtmExportError`] only covers the handful
+//! of outcomes a programmatic caller (as opposed to someone reading stderr)
+//! is likely to branch on. Every variant implements [`std::error::Error`]
+//! via `thiserror`, so it converts into an [`eyre::Report`] with `?`/`.into()`
+//! the same as any other error, and a caller who cares can get it back with
+//! `report.downcast_ref::<DtmExportError>()`.
+
+use std::path::PathBuf;
+
+/// A failure mode a caller of this crate's export functions might want to
+/// handle specifically, rather than just displaying.
+#[derive(Debug, thiserror::Error)]
+pub enum DtmExportError {
+    /// `path` doesn't exist on the local filesystem. Not raised for GDAL
+    /// virtual filesystem paths (`/vsi...`), since those aren't checkable
+    /// this way.
+    #[error("input not found: {}", .0.display())]
+    InputNotFound(PathBuf),
+
+    /// The input dataset opened fine but its band has a pixel type this
+    /// crate doesn't support reading as elevation data (complex types like
+    /// `CFloat32`). There's no separate GDAL-driver allowlist in this
+    /// crate, so this is also what a genuinely unsupported driver surfaces
+    /// as once GDAL hands back an unreadable band.
+    #[error("unsupported pixel type: {0}")]
+    UnsupportedDriver(String),
+
+    /// The input dataset has zero raster bands.
+    #[error("dataset has no raster bands")]
+    NoBands,
+
+    /// `path` already exists and neither `overwrite` nor an interactive
+    /// "yes" prompt (see [`crate::ASSUME_YES_ENV_VAR`]) got past it.
+    #[error("not overwriting existing file: {}", .0.display())]
+    OutputExists(PathBuf),
+
+    /// A GDAL operation failed; wrapped so `?` on a [`gdal::errors::GdalError`]
+    /// still produces a [`DtmExportError`] a caller can match on.
+    #[error(transparent)]
+    GdalError(#[from] gdal::errors::GdalError),
+}
@@ -0,0 +1,146 @@
+//! C ABI entry point for driving an export from a native (C/C++) Houdini
+//! plugin that can't link Rust directly.
+//!
+//! This is deliberately a thin, fixed-arity wrapper around
+//! [`crate::export_dtm_to_exr`] rather than a 1:1 FFI mirror of its full
+//! (and still growing) parameter list -- everything not exposed here
+//! (cropping, colormaps, graticules, ...) keeps the same defaults the
+//! Python bindings in `gdal-dtm-exporter-py` use. A caller that needs those
+//! should shell out to the CLI binary instead.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::path::PathBuf;
+
+use gdal::raster::ResampleAlg;
+
+use crate::formats::{ColorSpace, ExrChannels, ExrCompression, Tonemap};
+use crate::{export_dtm_to_exr, BandSelection, DtmOutputFormat, NodataAs};
+
+/// Return codes for [`dtm_export_to_exr`].
+#[repr(i32)]
+pub enum DtmExportStatus {
+    Ok = 0,
+    /// `input`/`output_dir` was null or not valid UTF-8.
+    InvalidArgument = -1,
+    /// The export itself failed (bad input raster, I/O error, ...). The
+    /// reason is logged through this crate's usual `log` output, not
+    /// returned here -- there's no caller-owned buffer for an error message.
+    ExportFailed = -2,
+    /// The export succeeded but its output path didn't fit in
+    /// `output_buf`; nothing was written to it.
+    BufferTooSmall = -3,
+}
+
+/// Exports `input` to an OpenEXR heightfield under `output_dir`, writing the
+/// output file's path as a NUL-terminated UTF-8 string into `output_buf` and
+/// returning a [`DtmExportStatus`] (as a plain `i32` for C's benefit).
+///
+/// `wsf` is the window scale factor (see [`crate::export_dtm_to_exr`]'s own
+/// doc comment).
+///
+/// # Safety
+///
+/// `input` and `output_dir` must be non-null, NUL-terminated, valid UTF-8 C
+/// strings; neither pointer is retained past this call. `output_buf` may be
+/// null, or `output_buf_len` 0 (either one skips writing the output path and
+/// returns [`DtmExportStatus::Ok`]); otherwise `output_buf` must point to at
+/// least `output_buf_len` writable bytes; this function never writes more
+/// than that, NUL terminator included, and returns
+/// [`DtmExportStatus::BufferTooSmall`] without touching `output_buf` at all
+/// if the path doesn't fit.
+///
+/// This function is safe to call concurrently from multiple threads,
+/// including from more than one at once, as long as two concurrent calls
+/// aren't given the same `input`/`output_dir` pair with `overwrite` false --
+/// the same restriction already applies to [`crate::export_dtm_to_exr`]
+/// itself, whose interactive overwrite prompt isn't safe to race.
+#[no_mangle]
+pub unsafe extern "C" fn dtm_export_to_exr(
+    input: *const c_char,
+    output_dir: *const c_char,
+    wsf: usize,
+    normalize: bool,
+    overwrite: bool,
+    output_buf: *mut c_char,
+    output_buf_len: usize,
+) -> i32 {
+    if input.is_null() || output_dir.is_null() {
+        return DtmExportStatus::InvalidArgument as i32;
+    }
+
+    let input = match CStr::from_ptr(input).to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(_) => return DtmExportStatus::InvalidArgument as i32,
+    };
+    let output_dir = match CStr::from_ptr(output_dir).to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(_) => return DtmExportStatus::InvalidArgument as i32,
+    };
+
+    let result = export_dtm_to_exr(
+        &input,
+        &output_dir,
+        None, // output_name
+        wsf,
+        None, // bbox
+        normalize,
+        overwrite,
+        false, // flip_y
+        false, // flip_x
+        false, // normalize_per_tile
+        None,  // manual_range
+        None,  // percentile_range
+        1.0,   // vertical_exaggeration
+        None,  // nodata_color
+        NodataAs::Zero,
+        false, // write_aux_xml
+        false, // world_file
+        false, // stats_json
+        ColorSpace::Linear,
+        Tonemap::Linear, // tonemap
+        2.2,             // gamma
+        None,            // graticule_spacing
+        [1.0, 0.0, 0.0], // graticule_color
+        0.5,             // graticule_opacity
+        false,           // use_metadata_offset
+        ResampleAlg::Bilinear,
+        0, // tile_overlap
+        None, // fill_voids
+        BandSelection::All,
+        DtmOutputFormat::Exr,
+        ExrCompression::Zip,
+        ExrChannels::Rgb,
+        false,     // build_overviews
+        "AVERAGE", // overview_resample
+        false,     // exr_tiled
+        (128, 128), // exr_tile_size
+        false,     // mipmaps
+        false, // with_mask
+        None,  // colormap
+        None,  // cancel
+        None,  // threads
+        None,  // progress
+    );
+
+    let output_path = match result {
+        Ok(path) => path,
+        Err(_) => return DtmExportStatus::ExportFailed as i32,
+    };
+
+    if output_buf.is_null() || output_buf_len == 0 {
+        return DtmExportStatus::Ok as i32;
+    }
+
+    let path_str = output_path.to_string_lossy();
+    let bytes = path_str.as_bytes();
+    if bytes.len() + 1 > output_buf_len {
+        return DtmExportStatus::BufferTooSmall as i32;
+    }
+
+    let dst = std::slice::from_raw_parts_mut(output_buf as *mut u8, output_buf_len);
+    dst[..bytes.len()].copy_from_slice(bytes);
+    dst[bytes.len()] = 0;
+
+    DtmExportStatus::Ok as i32
+}
@@ -0,0 +1,271 @@
+//! Low-pass filtering for building antialiased decimated proxies.
+//!
+//! GDAL's own resamplers (nearest/bilinear) alias on high-frequency terrain
+//! when used for heavy decimation; a proper low-pass before downsampling
+//! avoids that at the cost of a slightly softer result.
+
+/// How a decimated proxy should be produced from a full-resolution grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownsampleMethod {
+    /// Plain box average over each output cell's source footprint.
+    Average,
+    /// Gaussian low-pass (sigma derived from the decimation factor) before
+    /// nearest-cell sampling.
+    Gaussian,
+    /// Windowed-sinc (a=2) low-pass before nearest-cell sampling; sharper
+    /// than Gaussian at the cost of ringing near hard edges/no-data.
+    Lanczos,
+}
+
+/// Builds a 1D Gaussian kernel with the given `sigma`, normalized to sum to 1.
+fn gaussian_kernel_1d(sigma: f32) -> Vec<f32> {
+    let radius = (sigma * 3.0).ceil().max(1.0) as isize;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| {
+            let x = i as f32;
+            (-(x * x) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+
+    let sum: f32 = kernel.iter().sum();
+    for value in kernel.iter_mut() {
+        *value /= sum;
+    }
+    kernel
+}
+
+/// Lanczos-a=2 windowed-sinc kernel, normalized to sum to 1.
+///
+/// `scale` widens the lobe to match the decimation `factor` it's being
+/// built for -- without it, the window cutoff `|x| >= a` zeroes every tap
+/// beyond the first couple of pixels regardless of how large `radius` is,
+/// collapsing the kernel to a handful of taps no matter the decimation
+/// factor and leaving high-frequency content unfiltered (worse aliasing
+/// than plain averaging, not less).
+fn lanczos_kernel_1d(radius: isize, scale: f32) -> Vec<f32> {
+    const A: f32 = 2.0;
+
+    let sinc = |x: f32| if x == 0.0 { 1.0 } else { (std::f32::consts::PI * x).sin() / (std::f32::consts::PI * x) };
+
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| {
+            let x = i as f32 / scale;
+            if x.abs() >= A {
+                0.0
+            } else {
+                sinc(x) * sinc(x / A)
+            }
+        })
+        .collect();
+
+    let sum: f32 = kernel.iter().sum();
+    if sum.abs() > f32::EPSILON {
+        for value in kernel.iter_mut() {
+            *value /= sum;
+        }
+    }
+    kernel
+}
+
+fn convolve_separable(values: &[f32], width: usize, height: usize, kernel: &[f32]) -> Vec<f32> {
+    let radius = (kernel.len() / 2) as isize;
+    let mut horizontal = vec![0.0f32; width * height];
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = 0.0;
+            for (k, &weight) in kernel.iter().enumerate() {
+                let sample_x = (x as isize + k as isize - radius).clamp(0, width as isize - 1) as usize;
+                acc += values[y * width + sample_x] * weight;
+            }
+            horizontal[y * width + x] = acc;
+        }
+    }
+
+    let mut result = vec![0.0f32; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = 0.0;
+            for (k, &weight) in kernel.iter().enumerate() {
+                let sample_y = (y as isize + k as isize - radius).clamp(0, height as isize - 1) as usize;
+                acc += horizontal[sample_y * width + x] * weight;
+            }
+            result[y * width + x] = acc;
+        }
+    }
+
+    result
+}
+
+/// Low-pass filters `values` ahead of a `factor`-to-1 decimation, using
+/// `method`'s kernel (sigma/radius derived from `factor`); returns a grid the
+/// same `width`x`height` shape as `values`, ready for a caller to decimate
+/// with its own indexing scheme (stride-pick, nearest-sample, ...) afterward.
+///
+/// `Average` is a no-op -- plain box/stride decimation already pools or
+/// nearest-picks without ringing, so there's nothing to pre-filter.
+pub fn low_pass_for_decimation(values: &[f32], width: usize, height: usize, factor: usize, method: DownsampleMethod) -> Vec<f32> {
+    match method {
+        DownsampleMethod::Average => values.to_vec(),
+        DownsampleMethod::Gaussian => {
+            let sigma = factor as f32 / 2.0;
+            convolve_separable(values, width, height, &gaussian_kernel_1d(sigma))
+        }
+        DownsampleMethod::Lanczos => {
+            let radius = (factor as isize * 2).max(2);
+            convolve_separable(values, width, height, &lanczos_kernel_1d(radius, factor as f32))
+        }
+    }
+}
+
+/// Decimates a `width`x`height` grid by `factor` using `method`, returning
+/// the new grid and its dimensions.
+///
+/// `Average` pools each output cell's source footprint directly.
+/// `Gaussian`/`Lanczos` low-pass the full-resolution grid first (via
+/// [`low_pass_for_decimation`]) and then nearest-sample the decimated grid,
+/// which suppresses aliasing that plain averaging or nearest-neighbor lets
+/// through on rugged terrain.
+pub fn downsample_with_method(
+    values: &[f32],
+    width: usize,
+    height: usize,
+    factor: usize,
+    method: DownsampleMethod,
+) -> (Vec<f32>, usize, usize) {
+    let out_w = (width / factor).max(1);
+    let out_h = (height / factor).max(1);
+
+    match method {
+        DownsampleMethod::Average => {
+            let mut out = vec![0.0f32; out_w * out_h];
+            for oy in 0..out_h {
+                for ox in 0..out_w {
+                    let mut acc = 0.0;
+                    let mut count = 0;
+                    for dy in 0..factor {
+                        for dx in 0..factor {
+                            let sx = ox * factor + dx;
+                            let sy = oy * factor + dy;
+                            if sx < width && sy < height {
+                                acc += values[sy * width + sx];
+                                count += 1;
+                            }
+                        }
+                    }
+                    out[oy * out_w + ox] = acc / count.max(1) as f32;
+                }
+            }
+            (out, out_w, out_h)
+        }
+        DownsampleMethod::Gaussian | DownsampleMethod::Lanczos => {
+            let filtered = low_pass_for_decimation(values, width, height, factor, method);
+            nearest_sample(&filtered, width, height, out_w, out_h)
+        }
+    }
+}
+
+fn nearest_sample(values: &[f32], width: usize, height: usize, out_w: usize, out_h: usize) -> (Vec<f32>, usize, usize) {
+    let mut out = vec![0.0f32; out_w * out_h];
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let sx = (ox * width / out_w).min(width - 1);
+            let sy = (oy * height / out_h).min(height - 1);
+            out[oy * out_w + ox] = values[sy * width + sx];
+        }
+    }
+    (out, out_w, out_h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sum of squared first differences, a rough proxy for how much
+    /// high-frequency energy (aliasing-prone detail) a grid still has --
+    /// lower is smoother. Used below the same way the request that added
+    /// `DownsampleMethod` asked to measure a low-pass's effect.
+    fn high_frequency_energy(values: &[f32], width: usize, height: usize) -> f32 {
+        let mut energy = 0.0;
+        for y in 0..height {
+            for x in 0..width {
+                let center = values[y * width + x];
+                if x + 1 < width {
+                    let d = center - values[y * width + x + 1];
+                    energy += d * d;
+                }
+                if y + 1 < height {
+                    let d = center - values[(y + 1) * width + x];
+                    energy += d * d;
+                }
+            }
+        }
+        energy
+    }
+
+    /// A synthetic "rugged terrain" grid: a low-frequency slope with a
+    /// high-frequency checkerboard ripple riding on top of it, so a good
+    /// low-pass has real high-frequency content to remove.
+    fn rugged_grid(width: usize, height: usize) -> Vec<f32> {
+        (0..height)
+            .flat_map(|y| {
+                (0..width).map(move |x| {
+                    let slope = (x + y) as f32 * 0.1;
+                    let ripple = if (x + y) % 2 == 0 { 10.0 } else { -10.0 };
+                    slope + ripple
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gaussian_and_lanczos_reduce_high_frequency_energy_versus_average() {
+        let width = 64;
+        let height = 64;
+        let factor = 4;
+        let grid = rugged_grid(width, height);
+
+        let average = low_pass_for_decimation(&grid, width, height, factor, DownsampleMethod::Average);
+        let gaussian = low_pass_for_decimation(&grid, width, height, factor, DownsampleMethod::Gaussian);
+        let lanczos = low_pass_for_decimation(&grid, width, height, factor, DownsampleMethod::Lanczos);
+
+        let average_energy = high_frequency_energy(&average, width, height);
+        let gaussian_energy = high_frequency_energy(&gaussian, width, height);
+        let lanczos_energy = high_frequency_energy(&lanczos, width, height);
+
+        assert!(
+            gaussian_energy < average_energy,
+            "gaussian ({gaussian_energy}) should be smoother than average ({average_energy})"
+        );
+        assert!(
+            lanczos_energy < average_energy,
+            "lanczos ({lanczos_energy}) should be smoother than average ({average_energy}), not worse"
+        );
+    }
+
+    #[test]
+    fn lanczos_kernel_widens_with_scale() {
+        // At scale 1 (no decimation), the window cuts off right after the
+        // center tap; widening the scale should let taps further from the
+        // center keep contributing instead of being zeroed by the window.
+        let narrow = lanczos_kernel_1d(4, 1.0);
+        let wide = lanczos_kernel_1d(4, 4.0);
+
+        let narrow_nonzero = narrow.iter().filter(|&&w| w != 0.0).count();
+        let wide_nonzero = wide.iter().filter(|&&w| w != 0.0).count();
+
+        assert!(
+            wide_nonzero > narrow_nonzero,
+            "scale=4 kernel ({wide_nonzero} nonzero taps) should use more taps than scale=1 ({narrow_nonzero})"
+        );
+    }
+
+    #[test]
+    fn average_downsample_is_a_no_op_low_pass() {
+        let width = 8;
+        let height = 8;
+        let grid = rugged_grid(width, height);
+
+        assert_eq!(low_pass_for_decimation(&grid, width, height, 2, DownsampleMethod::Average), grid);
+    }
+}
@@ -0,0 +1,183 @@
+//! Registry of supported output formats.
+//!
+//! This is a hand-maintained list, not a reflection of [`crate::DtmOutputFormat`]
+//! or any writer's own types -- there's no single registry those writers plug
+//! into. Whoever adds a new exporter (or a new [`crate::DtmOutputFormat`]
+//! variant) needs to add an entry here too, or `list-formats` will silently
+//! under-report it.
+
+/// An output format the exporter knows how to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Exr,
+    ExrPackage,
+    GeoTiff,
+    Png16,
+    Png,
+    RawMmap,
+    MultibandTiff,
+    Ply,
+    Obj,
+    Usda,
+    GeoJsonContours,
+    ShapefileContours,
+}
+
+/// Color space a normalized/visualization EXR's values are encoded in.
+///
+/// Raw elevation data is always linear regardless of this setting -- it
+/// only applies to the normalized grayscale output of [`crate::export_dtm_to_exr`],
+/// which otherwise gets misread as a transfer-curved image by compositing
+/// tools that assume EXR means linear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    #[default]
+    Linear,
+    Srgb,
+}
+
+/// Tone curve applied to a normalized `[0, 1]` elevation before it's written
+/// to the grayscale/colormap EXR output, purely as a visualization aid for
+/// terrain whose dynamic range (deep canyons next to high peaks) gets
+/// crushed by plain linear normalization.
+///
+/// Unlike [`ColorSpace`], this isn't a transfer curve a downstream tool
+/// needs to know about to read the image correctly -- it permanently
+/// reshapes the normalized value itself, the same way a colormap does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tonemap {
+    #[default]
+    Linear,
+    /// Logarithmic compression, brightening low-elevation detail at the
+    /// expense of the high end.
+    Log,
+    /// Power curve: `normalized.powf(1.0 / gamma)`.
+    Gamma,
+}
+
+/// Bit depth for [`crate::export_dtm_to_png`]'s quantized output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngBitDepth {
+    Eight,
+    Sixteen,
+}
+
+/// Compression codec for [`crate::export_dtm_to_exr`]'s EXR output, passed
+/// through to the `exr` crate's writer. Names match the codec names
+/// OpenEXR itself uses, including the easily-confused `Zip`/`Zips` pair
+/// (16-scanline vs. single-scanline zip blocks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExrCompression {
+    None,
+    Rle,
+    /// 16-scanline ZIP blocks -- a reasonable size/speed tradeoff for
+    /// lossless float data, and this crate's default.
+    #[default]
+    Zip,
+    /// Single-scanline ZIP blocks -- better random access, worse ratio.
+    Zips,
+    Piz,
+    Pxr24,
+}
+
+/// Channel layout for [`crate::export_dtm_to_exr`]'s grayscale (no colormap)
+/// output.
+///
+/// [`ExrChannels::Gray`] writes a single `Y` channel, a third the file size
+/// of replicating the same height into R, G and B. Ignored in favor of
+/// [`ExrChannels::Rgb`] whenever a colormap is applied, since a colormap's
+/// whole point is distinct per-channel color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExrChannels {
+    Gray,
+    #[default]
+    Rgb,
+}
+
+/// Whether a PNG written by [`crate::export_dtm_to_png`] is a color-managed
+/// visualization image or a raw data carrier.
+///
+/// [`PngMode::Visualization`] tags the file with a gAMA/sRGB chunk (per
+/// [`ColorSpace`]) so viewers all render it the same way. [`PngMode::Data`]
+/// writes the identical pixel values but omits the chunk, so a tool reading
+/// the quantized values back out isn't handed a color-managed image it then
+/// has to un-correct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngMode {
+    Visualization,
+    Data,
+}
+
+/// Metadata describing one entry in the format registry.
+#[derive(Debug, Clone, Copy)]
+pub struct FormatInfo {
+    pub format: OutputFormat,
+    pub extension: &'static str,
+    pub preserves_georeferencing: bool,
+    pub description: &'static str,
+}
+
+/// Returns every format the exporter currently supports.
+pub fn list_formats() -> Vec<FormatInfo> {
+    vec![FormatInfo {
+        format: OutputFormat::Exr,
+        extension: "exr",
+        preserves_georeferencing: false,
+        description: "OpenEXR, suitable for Houdini heightfields and compositing.",
+    }, FormatInfo {
+        format: OutputFormat::ExrPackage,
+        extension: "exr",
+        preserves_georeferencing: false,
+        description: "Multi-part EXR with elevation, normals and a valid-pixel mask.",
+    }, FormatInfo {
+        format: OutputFormat::GeoTiff,
+        extension: "tif",
+        preserves_georeferencing: true,
+        description: "Float32 GeoTIFF, keeping the source geotransform and projection.",
+    }, FormatInfo {
+        format: OutputFormat::Png16,
+        extension: "png",
+        preserves_georeferencing: false,
+        description: "Single-channel 16-bit PNG, normalized elevation quantized to 0-65535.",
+    }, FormatInfo {
+        format: OutputFormat::Png,
+        extension: "png",
+        preserves_georeferencing: false,
+        description: "8- or 16-bit PNG with separate visualization/data color tagging.",
+    }, FormatInfo {
+        format: OutputFormat::RawMmap,
+        extension: "raw32",
+        preserves_georeferencing: false,
+        description: "Headerless row-major float32 raster for memory-mapped reads.",
+    }, FormatInfo {
+        format: OutputFormat::MultibandTiff,
+        extension: "tif",
+        preserves_georeferencing: true,
+        description: "Multi-band float32 GeoTIFF packing several rasters into one file.",
+    }, FormatInfo {
+        format: OutputFormat::Ply,
+        extension: "ply",
+        preserves_georeferencing: false,
+        description: "Triangle mesh in PLY; no per-vertex normals are written.",
+    }, FormatInfo {
+        format: OutputFormat::Obj,
+        extension: "obj",
+        preserves_georeferencing: false,
+        description: "Triangle mesh in Wavefront OBJ; no per-vertex normals are written.",
+    }, FormatInfo {
+        format: OutputFormat::Usda,
+        extension: "usda",
+        preserves_georeferencing: false,
+        description: "Triangle mesh in USD ASCII, for import into USD-based pipelines.",
+    }, FormatInfo {
+        format: OutputFormat::GeoJsonContours,
+        extension: "geojson",
+        preserves_georeferencing: true,
+        description: "Elevation contour lines as GeoJSON features.",
+    }, FormatInfo {
+        format: OutputFormat::ShapefileContours,
+        extension: "shp",
+        preserves_georeferencing: true,
+        description: "Elevation contour lines as an ESRI Shapefile.",
+    }]
+}
@@ -0,0 +1,3816 @@
+//! Core conversion logic shared by the `gdal-dtm-exporter` CLI and GUI.
+//!
+//! Reads a DTM/DEM raster through GDAL and exports it as an OpenEXR image
+//! that can be pulled straight into a Houdini COP/heightfield network.
+
+pub mod colormap;
+pub mod contour;
+pub mod erosion;
+pub mod error;
+pub mod ffi;
+pub mod filters;
+pub mod formats;
+pub mod mesh;
+pub mod mosaic;
+pub mod reproject;
+pub mod terrain;
+
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use error::DtmExportError;
+use eyre::{eyre, Result};
+use gdal::raster::{Buffer, ResampleAlg};
+use gdal::{Dataset, DriverManager};
+use image::{Rgb, Rgb32FImage};
+use log::info;
+use rayon::prelude::*;
+
+use formats::{ColorSpace, ExrChannels, ExrCompression, PngBitDepth, PngMode, Tonemap};
+
+/// Remaps `value` from `from_range` into `to_range`, linearly.
+///
+/// Works for any type that supports the usual arithmetic operators, so it's
+/// equally at home mapping `f32` elevations and `f64` geotransform units.
+/// `from_range`/`to_range` don't need to be ascending -- an inverted
+/// `to_range` flips the mapping as expected.
+///
+/// `from_range` having zero span (`from_min == from_max`, e.g. a perfectly
+/// flat band) isn't rejected here: for a float `T` it divides by zero and
+/// silently produces `NaN`/`Inf` rather than panicking. Callers that can't
+/// rule that out -- most normalization call sites over raster stats can't --
+/// should either guard the flat case themselves or use
+/// [`checked_map_range`], which turns it into `None`.
+pub fn map_range<T>(value: T, from_range: (T, T), to_range: (T, T)) -> T
+where
+    T: Copy
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T>,
+{
+    let (from_min, from_max) = from_range;
+    let (to_min, to_max) = to_range;
+
+    to_min + (value - from_min) * (to_max - to_min) / (from_max - from_min)
+}
+
+/// [`map_range`], but returns `None` instead of dividing by zero when
+/// `from_range` has zero span, instead of handing back a `NaN`/`Inf` that
+/// would otherwise propagate silently into quantized output.
+pub fn checked_map_range<T>(value: T, from_range: (T, T), to_range: (T, T)) -> Option<T>
+where
+    T: Copy
+        + PartialEq
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T>,
+{
+    let (from_min, from_max) = from_range;
+    if from_min == from_max {
+        return None;
+    }
+
+    Some(map_range(value, from_range, to_range))
+}
+
+/// Inverse of the `normalize`d `[0, 1]` mapping `export_dtm_to_exr` writes
+/// into a layer's channels: recovers the original elevation a stored value
+/// of `value` came from, given the `min`/`max` the export normalized
+/// against (the same pair it now embeds in the `DTM_ElevationMin`/
+/// `DTM_ElevationMax` EXR header attributes).
+///
+/// Equivalent to `map_range(value as f64, (0.0, 1.0), (min, max))`, spelled
+/// out separately so a downstream reader doesn't have to reconstruct the
+/// forward mapping's argument order themselves.
+pub fn denormalize(value: f32, min: f64, max: f64) -> f64 {
+    map_range(value as f64, (0.0, 1.0), (min, max))
+}
+
+/// Computes the `(width, height)` of a `window_scale_factor`-sized tiling
+/// region over a `raster_w`x`raster_h` raster, clamped to at least one pixel
+/// per side.
+///
+/// `window_scale_factor` dividing evenly into `step_by` would otherwise
+/// produce a region size of 0 -- and panic -- once the factor exceeds the
+/// corresponding raster dimension. Warns (once) when that clamp kicks in, so
+/// an unreasonably large `--window-scale-factor` doesn't silently degrade to
+/// the equivalent of 1 instead of erroring or visibly warning.
+fn tile_region_size(raster_w: usize, raster_h: usize, window_scale_factor: usize) -> (usize, usize) {
+    let region_w = raster_w / window_scale_factor;
+    let region_h = raster_h / window_scale_factor;
+
+    if region_w == 0 || region_h == 0 {
+        log::warn!(
+            "--window-scale-factor {window_scale_factor} exceeds the raster's {raster_w}x{raster_h} size; clamping tile region to 1 pixel per affected axis"
+        );
+    }
+
+    (region_w.max(1), region_h.max(1))
+}
+
+/// Reads `band`'s full `raster_w`x`raster_h` extent in `region_w`x`region_h`
+/// tiles (see [`tile_region_size`]), calling `visit` once per tile with its
+/// `(x_offset, y_offset, tile_w, tile_h)` and the `tile_w * tile_h`
+/// row-major buffer read for it.
+///
+/// Clamps each tile's window to whatever extent remains at the raster's
+/// right/bottom edge instead of always reading a fixed `region_w`x
+/// `region_h` window -- GDAL's `RasterIO` rejects a window that runs past
+/// the raster, which a fixed-size tile grid does as soon as `raster_w`/
+/// `raster_h` isn't an exact multiple of the region size.
+fn read_band_tiled(
+    band: &gdal::raster::RasterBand,
+    raster_w: usize,
+    raster_h: usize,
+    region_w: usize,
+    region_h: usize,
+    mut visit: impl FnMut(usize, usize, usize, usize, &[f32]) -> Result<()>,
+) -> Result<()> {
+    for y_offset in (0..raster_h).step_by(region_h) {
+        let tile_h = region_h.min(raster_h - y_offset);
+        for x_offset in (0..raster_w).step_by(region_w) {
+            let tile_w = region_w.min(raster_w - x_offset);
+            let buffer = band.read_as::<f32>((x_offset as isize, y_offset as isize), (tile_w, tile_h), (tile_w, tile_h), None)?;
+            visit(x_offset, y_offset, tile_w, tile_h, buffer.data())?;
+        }
+    }
+    Ok(())
+}
+
+/// Fills no-data pixels in a `w`x`h` row-major buffer whose neighborhood
+/// within `max_gap` pixels (Euclidean distance) contains at least one valid
+/// sample, replacing each with an inverse-distance weighted mean of the
+/// valid samples in that neighborhood. A no-data pixel with no valid sample
+/// within `max_gap` is left untouched, so a void wider than `max_gap` stays
+/// no-data instead of being smeared from samples too far away to be
+/// meaningful.
+///
+/// `O(w * h * max_gap^2)` -- fine for the scattered single-pixel voids this
+/// is meant for, but not a substitute for a real inpainting pass over large
+/// gaps.
+pub fn fill_voids_in_place(data: &mut [f32], w: usize, h: usize, nodata: f64, max_gap: usize) {
+    if max_gap == 0 {
+        return;
+    }
+
+    let source = data.to_vec();
+    for y in 0..h {
+        for x in 0..w {
+            let index = y * w + x;
+            if (source[index] as f64 - nodata).abs() >= f64::EPSILON {
+                continue;
+            }
+
+            let min_x = x.saturating_sub(max_gap);
+            let max_x = (x + max_gap).min(w.saturating_sub(1));
+            let min_y = y.saturating_sub(max_gap);
+            let max_y = (y + max_gap).min(h.saturating_sub(1));
+
+            let mut weighted_sum = 0.0f64;
+            let mut weight_total = 0.0f64;
+            for ny in min_y..=max_y {
+                for nx in min_x..=max_x {
+                    if nx == x && ny == y {
+                        continue;
+                    }
+
+                    let value = source[ny * w + nx];
+                    if (value as f64 - nodata).abs() < f64::EPSILON {
+                        continue;
+                    }
+
+                    let distance = (((nx as isize - x as isize).pow(2) + (ny as isize - y as isize).pow(2)) as f64).sqrt();
+                    if distance > max_gap as f64 {
+                        continue;
+                    }
+
+                    let weight = 1.0 / distance;
+                    weighted_sum += value as f64 * weight;
+                    weight_total += weight;
+                }
+            }
+
+            if weight_total > 0.0 {
+                data[index] = (weighted_sum / weight_total) as f32;
+            }
+        }
+    }
+}
+
+/// Default per-tile memory budget for [`suggest_window_scale_factor`], in
+/// bytes.
+pub const DEFAULT_MEMORY_BUDGET_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Standard overview level set [`export_dtm_to_exr`]'s `build_overviews`
+/// builds into a [`DtmOutputFormat::GeoTiff`] output -- the usual 2x/4x/8x/
+/// 16x pyramid most GIS viewers expect, skipping any level that would
+/// downsample the raster to less than one pixel on an axis.
+const OVERVIEW_LEVELS: [i32; 4] = [2, 4, 8, 16];
+
+/// Picks the smallest `window_scale_factor` whose resulting tile (see
+/// [`tile_region_size`]) stays under `memory_budget_bytes`, so a caller
+/// doesn't have to guess one by hand.
+///
+/// Assumes 4 bytes/pixel/band, matching the `f32` buffers
+/// [`export_dtm_to_exr`] actually reads tiles into. Never returns less than
+/// 1; a `raster_w`x`raster_h`x`band_count` raster whose single full-size
+/// tile already fits the budget gets a factor of 1.
+pub fn suggest_window_scale_factor(raster_w: usize, raster_h: usize, band_count: usize, memory_budget_bytes: u64) -> usize {
+    let full_tile_bytes = raster_w as u64 * raster_h as u64 * band_count as u64 * 4;
+    if full_tile_bytes <= memory_budget_bytes {
+        return 1;
+    }
+
+    let ratio = full_tile_bytes as f64 / memory_budget_bytes as f64;
+    (ratio.sqrt().ceil() as usize).max(1)
+}
+
+/// Computes the values at the `low`/`high` percentiles (0-100) of `band`'s
+/// valid (non-no-data) samples, after `elevation_offset` is applied, by
+/// reading the whole band into memory and sorting it.
+///
+/// This is a heavier one-time pass than [`gdal::raster::RasterBand::compute_raster_min_max`],
+/// which GDAL can sometimes answer from cached statistics, but there's no
+/// percentile equivalent exposed by the crate -- so exactness wins over
+/// speed here, same trade-off the CLI makes by reading band 1 in full to feed
+/// [`quantization_report`] for an offline report.
+fn band_percentile_range(
+    band: &gdal::raster::RasterBand,
+    x_offset: usize,
+    y_offset: usize,
+    raster_w: usize,
+    raster_h: usize,
+    elevation_offset: f64,
+    low: f64,
+    high: f64,
+) -> Result<(f64, f64)> {
+    let nodata = band.no_data_value();
+    let buffer = band.read_as::<f32>(
+        (x_offset as isize, y_offset as isize),
+        (raster_w, raster_h),
+        (raster_w, raster_h),
+        None,
+    )?;
+
+    let mut values: Vec<f64> = buffer
+        .data()
+        .iter()
+        .filter(|&&v| nodata.map(|nd| (v as f64 - nd).abs() >= f64::EPSILON).unwrap_or(true))
+        .map(|&v| v as f64 + elevation_offset)
+        .filter(|v| v.is_finite())
+        .collect();
+
+    if values.is_empty() {
+        return Err(eyre!("band has no valid (non-no-data) samples to compute a percentile range from"));
+    }
+
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).expect("non-finite values already filtered out"));
+
+    let pick = |percentile: f64| -> f64 {
+        let rank = (percentile / 100.0 * (values.len() - 1) as f64).round() as usize;
+        values[rank.min(values.len() - 1)]
+    };
+
+    Ok((pick(low), pick(high)))
+}
+
+/// Min/max over `band`'s valid (non-no-data) samples in the
+/// `x_offset`/`y_offset`/`raster_w`/`raster_h` window, for normalization.
+///
+/// [`gdal::raster::RasterBand::compute_raster_min_max`] wraps
+/// `GDALComputeRasterMinMax`, which scans raw pixel values and doesn't
+/// exclude the band's `no_data_value` -- so a DEM with e.g. a `-9999` void
+/// sentinel gets that sentinel folded into the min, crushing every real
+/// elevation into a tiny slice near the top of the normalized range. This
+/// reads the window itself (the same full-window read [`band_percentile_range`]
+/// already does) and scans it in memory, skipping no-data samples, instead.
+fn band_min_max_excluding_nodata(
+    band: &gdal::raster::RasterBand,
+    x_offset: usize,
+    y_offset: usize,
+    raster_w: usize,
+    raster_h: usize,
+) -> Result<(f64, f64)> {
+    let nodata = band.no_data_value();
+    let buffer = band.read_as::<f32>(
+        (x_offset as isize, y_offset as isize),
+        (raster_w, raster_h),
+        (raster_w, raster_h),
+        None,
+    )?;
+
+    let valid_values = buffer
+        .data()
+        .iter()
+        .filter(|&&v| nodata.map(|nd| (v as f64 - nd).abs() >= f64::EPSILON).unwrap_or(true));
+
+    let min = valid_values.clone().fold(f64::INFINITY, |acc, &v| acc.min(v as f64));
+    let max = valid_values.fold(f64::NEG_INFINITY, |acc, &v| acc.max(v as f64));
+
+    if !min.is_finite() || !max.is_finite() {
+        return Err(eyre!("band has no valid (non-no-data) samples to compute a min/max from"));
+    }
+
+    Ok((min, max))
+}
+
+/// Checks that `band` holds a real-valued pixel type before it's handed to
+/// `read_as::<f32>`, which silently produces nonsense (rather than erroring)
+/// on complex types like `CFloat32`.
+///
+/// Fails with [`DtmExportError::UnsupportedDriver`], downcastable out of the
+/// returned [`eyre::Report`] for a caller that wants to branch on it.
+fn check_supported_pixel_type(band: &gdal::raster::RasterBand) -> Result<()> {
+    use gdal::raster::GdalDataType;
+
+    match band.band_type() {
+        GdalDataType::CInt16 | GdalDataType::CInt32 | GdalDataType::CFloat32 | GdalDataType::CFloat64 => Err(
+            DtmExportError::UnsupportedDriver(format!(
+                "{:?} (pick a real-valued band)",
+                band.band_type()
+            ))
+            .into(),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Metadata keys some DTM/DEM providers use to store a vertical datum
+/// offset or reference elevation. Checked on both the dataset and band 1,
+/// in this order, by [`detect_elevation_offset`].
+const ELEVATION_OFFSET_METADATA_KEYS: &[&str] = &["REFERENCE_ELEVATION", "VERTICAL_DATUM_OFFSET"];
+
+/// Looks for a recognized elevation-offset metadata item on `dataset` or its
+/// first band, returning the key that matched and its parsed value.
+///
+/// Only [`ELEVATION_OFFSET_METADATA_KEYS`] are recognized; anything else is
+/// ignored rather than guessed at.
+fn detect_elevation_offset(dataset: &Dataset) -> Result<Option<(String, f64)>> {
+    let band = dataset.rasterband(1)?;
+
+    for &key in ELEVATION_OFFSET_METADATA_KEYS {
+        if let Some(raw) = dataset.metadata_item(key, "") {
+            if let Ok(value) = raw.parse::<f64>() {
+                return Ok(Some((key.to_string(), value)));
+            }
+        }
+        if let Some(raw) = band.metadata_item(key, "") {
+            if let Ok(value) = raw.parse::<f64>() {
+                return Ok(Some((key.to_string(), value)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether `path` names a GDAL virtual filesystem location (`/vsizip/`,
+/// `/vsicurl/`, `/vsis3/`, etc.) rather than a real path on the local
+/// filesystem.
+///
+/// `std::fs`/`Path::exists` checks don't mean anything for these -- the
+/// bytes live inside an archive or behind an HTTP endpoint -- so any code
+/// that gates on local presence before handing a path to GDAL needs to skip
+/// that check for a `/vsi...` path and let `Dataset::open` itself report
+/// whether it's reachable.
+pub fn is_vsi_path(path: &std::path::Path) -> bool {
+    path.to_string_lossy().starts_with("/vsi")
+}
+
+/// Confirms every band of `in_image_path` is actually readable before
+/// committing to a long export.
+///
+/// Reads a 1x1 window from each band, which is cheap relative to a full
+/// export but still exercises the driver and I/O path -- enough to catch a
+/// truncated file, a dead `/vsicurl/` endpoint, or an unreadable band, which
+/// would otherwise only surface partway through the real read loop. Fails on
+/// the first unreadable band with a message naming it.
+pub fn validate_input(in_image_path: &PathBuf) -> Result<()> {
+    if !is_vsi_path(in_image_path) && !in_image_path.exists() {
+        return Err(DtmExportError::InputNotFound(in_image_path.clone()).into());
+    }
+
+    let dataset = Dataset::open(in_image_path)?;
+    let band_count = dataset.raster_count();
+    if band_count == 0 {
+        return Err(DtmExportError::NoBands.into());
+    }
+
+    for band_index in 1..=band_count {
+        let band = dataset
+            .rasterband(band_index)
+            .map_err(|err| eyre!("band {band_index} is not readable: {err}"))?;
+        check_supported_pixel_type(&band)?;
+        band.read_as::<f32>((0, 0), (1, 1), (1, 1), None)
+            .map_err(|err| eyre!("band {band_index} failed a test read: {err}"))?;
+    }
+
+    Ok(())
+}
+
+/// Applies the sRGB transfer curve to a value already normalized to `[0, 1]`.
+///
+/// Used only for [`ColorSpace::Srgb`] visualization output; raw elevation
+/// data is never run through this, since it would corrupt the values.
+fn srgb_encode(linear: f32) -> f32 {
+    if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Reshapes a value already normalized to `[0, 1]` through `tonemap`'s tone
+/// curve, still landing in `[0, 1]` (`0` stays `0`, `1` stays `1`).
+///
+/// [`Tonemap::Log`] adds a small epsilon before taking the logarithm, so a
+/// pixel that normalized to exactly `0` -- routine for terrain whose
+/// elevation min is itself `0` (sea level) -- doesn't hit `ln(0)`.
+fn apply_tonemap(normalized: f32, tonemap: Tonemap, gamma: f32) -> f32 {
+    match tonemap {
+        Tonemap::Linear => normalized,
+        Tonemap::Gamma => normalized.max(0.0).powf(1.0 / gamma.max(f32::EPSILON)),
+        Tonemap::Log => {
+            const EPSILON: f32 = 1e-4;
+            let shifted = normalized.clamp(0.0, 1.0) + EPSILON;
+            let numerator = shifted.ln() - EPSILON.ln();
+            let denominator = (1.0 + EPSILON).ln() - EPSILON.ln();
+            (numerator / denominator).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Runs `write_fn` against a `.tmp` sibling of `final_path` and only renames
+/// it into place once `write_fn` returns successfully, so a reader can never
+/// observe a partially-written output file. The temp file is removed if
+/// `write_fn` fails.
+fn write_atomically<F>(final_path: &PathBuf, write_fn: F) -> Result<()>
+where
+    F: FnOnce(&PathBuf) -> Result<()>,
+{
+    let mut tmp_name = final_path.clone().into_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    match write_fn(&tmp_path) {
+        Ok(()) => {
+            std::fs::rename(&tmp_path, final_path)?;
+            Ok(())
+        }
+        Err(err) => {
+            std::fs::remove_file(&tmp_path).ok();
+            Err(err)
+        }
+    }
+}
+
+/// Name of the environment variable that, when set to `1`, makes
+/// [`confirm_overwrite`] behave as if `--yes`/`overwrite` had been passed.
+/// Flag > env var > interactive prompt, in that order of precedence.
+pub const ASSUME_YES_ENV_VAR: &str = "DTM_EXPORTER_ASSUME_YES";
+
+fn assume_yes_from_env() -> bool {
+    std::env::var(ASSUME_YES_ENV_VAR).as_deref() == Ok("1")
+}
+
+fn confirm_overwrite(output_path: &PathBuf) -> Result<bool> {
+    if !output_path.exists() {
+        return Ok(true);
+    }
+
+    if assume_yes_from_env() {
+        return Ok(true);
+    }
+
+    if !io::stdin().is_terminal() {
+        // No one's there to answer an interactive prompt -- block on
+        // `read_line` here and a headless/CI run (or the GUI, if it ever
+        // stopped passing `overwrite_output` through) would just hang.
+        // The caller already turns a `false` here into a
+        // `DtmExportError::OutputExists`, which is the right failure mode
+        // for automation to catch and handle itself.
+        return Ok(false);
+    }
+
+    print!(
+        "{} already exists, overwrite? [y/N] ",
+        output_path.display()
+    );
+    io::stdout().flush().ok();
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Which bands of a multi-band dataset [`export_dtm_to_exr`] writes into the
+/// output image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandSelection {
+    /// Map each band to its own output channel, in band order (band 1 -> R,
+    /// band 2 -> G, band 3 -> B). Bands beyond the third are skipped (neither
+    /// read nor written), with a warning.
+    All,
+    /// Map exactly one band (1-indexed, matching GDAL's convention) onto all
+    /// three output channels, producing the same grayscale image as before
+    /// this selection existed.
+    Single(usize),
+}
+
+/// How [`export_dtm_to_exr`] writes a sample that matches the band's
+/// no-data value, instead of its normalized elevation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodataAs {
+    /// Write `0.0`.
+    Zero,
+    /// Write `f32::NAN`, so downstream tools that check for it (rather than
+    /// treating every zero as data) can tell a void apart from sea level.
+    Nan,
+}
+
+/// Output format for [`export_dtm_to_exr`], choosing both the file
+/// extension and the encoder used to write it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DtmOutputFormat {
+    /// OpenEXR, streamed row-by-row (see [`export_dtm_to_exr`]'s doc comment).
+    Exr,
+    /// Float32 GeoTIFF, preserving the source dataset's geotransform and
+    /// projection via GDAL's create/copy so the result stays georeferenced.
+    GeoTiff,
+    /// A single-channel 16-bit PNG, same normalization as `export_dtm_to_exr`
+    /// but quantized; for a dedicated 8-bit-or-16-bit PNG with more control
+    /// over gAMA/sRGB tagging, use [`export_dtm_to_png`] instead. Requires
+    /// `normalize` (see [`export_dtm_to_exr`]'s doc comment).
+    Png16,
+}
+
+/// Reads `in_image_path` through GDAL and writes a normalized OpenEXR image
+/// into `export_dir`, named after the input file's stem.
+///
+/// `in_image_path` and `export_dir` take `impl AsRef<Path>` rather than
+/// `&PathBuf`, so a caller holding a `&Path`, `&str`, or `&OsStr` doesn't
+/// need to allocate a `PathBuf` just to call this.
+///
+/// `window_scale_factor` controls how the raster is chunked while reading:
+/// the raster is divided into a `window_scale_factor`-sized grid of regions
+/// that are read one row-band at a time. The EXR output itself is streamed
+/// pixel-by-pixel to `exr`'s writer rather than built up as one in-memory
+/// image first, so peak memory stays proportional to one `region_size_h`-
+/// tall row of tiles (times the number of bands written) rather than the
+/// full raster.
+///
+/// `resample` picks the algorithm GDAL uses when a tile's read window
+/// doesn't land on whole source pixels (e.g. under `window_scale_factor`
+/// tiling); callers wanting the old hardcoded behavior should pass
+/// `ResampleAlg::Bilinear`.
+///
+/// `threads` caps the size of the rayon pool used to read and normalize
+/// tiles in parallel; `None` or `Some(0)` uses rayon's default (one thread
+/// per core). Each tile opens its own `Dataset` handle, since a single GDAL
+/// dataset isn't safe to share across threads.
+///
+/// `progress`, when given, is invoked with a 0.0-1.0 fraction after each
+/// row of tiles is computed, accounting for both the row count and how many
+/// bands will actually be written (per `band_selection`), since every
+/// active band is folded into a row's computation together.
+///
+/// `output_format` picks the encoder; only [`DtmOutputFormat::Exr`] writes
+/// incrementally via the row cache described above. [`DtmOutputFormat::GeoTiff`]
+/// and [`DtmOutputFormat::Png16`] still compute one row of tiles at a time,
+/// but since GDAL's writer and the `png` crate's encoder both expect rows in
+/// order, each row is written immediately instead of going through a
+/// lookup cache.
+///
+/// `manual_range`, when given, replaces the auto-computed min/max (and
+/// skips `normalize_per_tile` entirely) so separately exported tiles land
+/// on the same elevation scale. Values outside the range are clamped to
+/// `[0, 1]` instead of extrapolated, unlike the auto-computed path.
+///
+/// `percentile_range`, when given, replaces the auto-computed min/max with
+/// the values sitting at the given `(low, high)` percentiles (0-100) of each
+/// band's valid samples instead, so a handful of outlier pits or spikes
+/// don't wash out the normalization of everything else. Like `manual_range`,
+/// it clamps rather than extrapolates, and is mutually exclusive with it --
+/// `manual_range` wins if both are somehow set.
+///
+/// `with_mask` only affects [`DtmOutputFormat::Exr`]: it adds a 4th `A`
+/// channel, 1.0 for samples read from real data and 0.0 for ones that
+/// matched the source band's no-data value, so compositing tools can tell
+/// a void apart from a valid sample that happens to map to the same color.
+///
+/// `colormap`, when given, replaces the usual grayscale (R == G == B)
+/// output with [`colormap::apply`]'s color for the normalized value --
+/// only meaningful with [`BandSelection::Single`]; [`BandSelection::All`]
+/// already uses the channels for separate bands.
+///
+/// `cancel`, when given, is checked at the top of every row of tiles; once
+/// set, the export stops early with an `Err` instead of finishing the
+/// write. Checked per row rather than per pixel since a single row of
+/// tiles is already the smallest unit of work the [`DtmOutputFormat::GeoTiff`]
+/// and [`DtmOutputFormat::Png16`] paths commit incrementally. The
+/// [`DtmOutputFormat::Exr`] path pulls rows through a closure the `exr`
+/// crate's streaming writer calls directly, which can't propagate a
+/// `Result` -- cancelling an EXR export still stops the row computation
+/// but surfaces as a panic from that closure rather than a clean `Err`.
+///
+/// `vertical_exaggeration` multiplies every elevation sample (after
+/// `elevation_offset` is applied) before it's used any further. With
+/// `normalize` on, this happens before the min/max computed from the
+/// un-exaggerated band is used to map the value into `[0, 1]`, so values
+/// above 1.0x push past the original range instead of cancelling out --
+/// exactly the stretched-contrast look vertical exaggeration is for. With
+/// `normalize` off, the stored elevation is simply scaled.
+///
+/// `stats_json`, when set, writes a `<output>.json` sidecar alongside the
+/// export recording the source path, raster dimensions, spatial reference
+/// name, normalization mode and the range it resolved to, the window scale
+/// factor, and each exported band's min/max/no-data value -- a provenance
+/// record for pipelines that want to know exactly what a given export was
+/// built from without re-deriving it from the (lossier) output image.
+///
+/// `bbox`, when given, is a `(min_x, min_y, max_x, max_y)` pixel-space
+/// rectangle (max exclusive) that restricts both the read window and the
+/// output image to that sub-rectangle, instead of the whole raster. Out of
+/// range bounds are clamped to the raster's extent rather than erroring, so
+/// a bbox drawn against slightly stale dimensions still exports something
+/// sane. Any georeferencing this export writes out (the EXR's
+/// `GDAL_GeoTransform` attribute, `--world-file`, `--multiband-tiff`'s
+/// GeoTIFF header) has its origin shifted to match, so the crop still lines
+/// up spatially with the source. `normalize`'s auto-computed min/max is the
+/// one exception: it's still drawn from the whole raster (`percentile_range`
+/// and `manual_range` aren't), so elevation scale stays comparable across
+/// separately exported crops of the same source.
+///
+/// `exr_compression` selects the compression codec the `exr` crate writes
+/// the EXR output with. [`formats::ExrCompression::Zip`] (16-scanline ZIP
+/// blocks) matches the codec [`exr::prelude::Encoding::FAST_LOSSLESS`] used
+/// before this was configurable, so it stays the default; the other
+/// variants trade encode speed, random-access friendliness, and file size
+/// against each other without affecting the stored elevation values.
+///
+/// `channels`, with [`BandSelection::Single`] and no `colormap`, chooses
+/// between a single-channel `Y` EXR and the default RGB triple (the same
+/// height repeated across R, G and B). Ignored -- forced to
+/// [`formats::ExrChannels::Rgb`] -- whenever a colormap is in play or
+/// [`BandSelection::All`] is used, since both already need distinct
+/// per-channel values.
+///
+/// On success, logs the total wall-clock time and throughput at info level
+/// (e.g. "exported 4096x4096 in 2.3s (7.1 MPix/s)"), timed from the moment
+/// this function starts reading the source dataset through its last sidecar
+/// write -- read, normalize and write time are all included, since that's
+/// the cost a caller actually pays per export.
+///
+/// `output_name`, when given, overrides the input file's stem (and the EXR
+/// layer's own name) as the output file's base name; `export_dir` and the
+/// extension `output_format` implies still apply on top of it. Pass `None`
+/// to keep deriving the name from `in_image_path` as before.
+///
+/// [`DtmOutputFormat::Png16`] requires `normalize`: a 16-bit PNG channel has
+/// nowhere to put a raw elevation's range, so an unnormalized value would
+/// silently clamp to black or white instead of erroring. Errors up front
+/// with `normalize` off rather than writing a misleading image.
+///
+/// `tile_overlap` expands each tile's GDAL read window by this many pixels
+/// on every side (clamped at the raster's own edges) before `resample` runs,
+/// then discards the halo before the tile's interior is written out -- so a
+/// resample kernel with a support radius wider than one pixel (cubic,
+/// Lanczos) has real neighboring source pixels to draw on right up to a
+/// tile boundary, instead of only whatever lies inside that tile's own
+/// window. `0` keeps the previous tile-is-exactly-its-window behavior.
+///
+/// With `normalize` on, the [`DtmOutputFormat::Exr`] path also embeds the
+/// range it normalized against as `DTM_ElevationMin`/`DTM_ElevationMax`
+/// header attributes (and `DTM_ElevationUnit`, if the source band declares
+/// one) so a stored `[0, 1]` value can be mapped back to a real-world
+/// elevation with [`denormalize`] without needing the source dataset on hand.
+///
+/// `fill_voids`, when given, fills a no-data pixel with an inverse-distance
+/// weighted mean of the valid samples within that many pixels of it, via
+/// [`fill_voids_in_place`], before normalization/colormapping runs -- so a
+/// scattered single-pixel void gets a plausible elevation instead of
+/// showing up as a hole (or `nodata_color`) in the export. A void wider
+/// than `fill_voids` in every direction stays no-data, since there's no
+/// valid sample close enough to interpolate from. Like `tile_overlap`, this
+/// widens each tile's GDAL read window so pixels near a tile boundary still
+/// see real neighbors across it.
+///
+/// `flip_y`/`flip_x` mirror the output image vertically/horizontally --
+/// useful against a target (some game engines, some point-cloud tools) that
+/// assumes a bottom-left rather than GDAL's top-left pixel origin. Any
+/// georeferencing this export writes out (the EXR `GDAL_GeoTransform`
+/// attribute, the GeoTIFF header, `--world-file`) is adjusted to match via
+/// [`flipped_geo_transform`], so it still describes where the flipped
+/// pixels actually sit rather than the unflipped raster's layout.
+///
+/// `tonemap` reshapes the normalized elevation through a tone curve before
+/// it's written -- see [`apply_tonemap`]. [`Tonemap::Gamma`] uses `gamma` as
+/// its exponent; ignored otherwise.
+///
+/// `build_overviews`, with [`DtmOutputFormat::GeoTiff`], builds `2`/`4`/
+/// `8`/`16`x overview levels into the output GeoTIFF after the full
+/// resolution band is written, via GDAL's own `BuildOverviews`, resampled
+/// with `overview_resample` (one of the strings GDAL's
+/// `GDALRegenerateOverviews` accepts, e.g. `"average"`, `"nearest"`,
+/// `"cubic"`). Ignored by every other output format. Logs the levels it
+/// built at info level on success.
+///
+/// `exr_tiled`, with [`DtmOutputFormat::Exr`], writes the output using the
+/// `exr` crate's tiled block layout instead of scanlines, with each tile
+/// sized `exr_tile_size` (width, height) -- see [`exr_encoding_for`]. A
+/// reader that only wants a crop of the image can then seek straight to the
+/// tiles covering it instead of decoding full-width scanlines it doesn't
+/// need. `exr_tile_size` is ignored when `exr_tiled` is false.
+///
+/// `mipmaps`, with [`DtmOutputFormat::Exr`], additionally writes a mip
+/// pyramid: level 0 is the full-resolution image, and each further level
+/// halves both dimensions (rounding down to a minimum of `1`) until a 1x1
+/// level is reached, via [`downsample_mip_level`]'s no-data-aware box
+/// filter -- a pixel with zero valid (non-transparent) samples under it
+/// stays no-data in the level above rather than being dragged toward a
+/// sentinel value. `exrs`' per-pixel writer only supports a single
+/// resolution, so each level is written as its own named layer
+/// (`{name}.mip0`, `{name}.mip1`, ...) in the same multi-part file instead
+/// of a true OpenEXR `MIPMAP_LEVELS` layer.
+#[allow(clippy::too_many_arguments)]
+pub fn export_dtm_to_exr(
+    in_image_path: impl AsRef<Path>,
+    export_dir: impl AsRef<Path>,
+    output_name: Option<&str>,
+    window_scale_factor: usize,
+    bbox: Option<(usize, usize, usize, usize)>,
+    normalize: bool,
+    overwrite: bool,
+    flip_y: bool,
+    flip_x: bool,
+    normalize_per_tile: bool,
+    manual_range: Option<(f64, f64)>,
+    percentile_range: Option<(f64, f64)>,
+    vertical_exaggeration: f32,
+    nodata_color: Option<[f32; 3]>,
+    nodata_as: NodataAs,
+    write_aux_xml: bool,
+    world_file: bool,
+    stats_json: bool,
+    colorspace: ColorSpace,
+    tonemap: Tonemap,
+    gamma: f32,
+    graticule_spacing: Option<f64>,
+    graticule_color: [f32; 3],
+    graticule_opacity: f32,
+    use_metadata_offset: bool,
+    resample: ResampleAlg,
+    tile_overlap: usize,
+    fill_voids: Option<usize>,
+    band_selection: BandSelection,
+    output_format: DtmOutputFormat,
+    exr_compression: ExrCompression,
+    channels: ExrChannels,
+    build_overviews: bool,
+    overview_resample: &str,
+    exr_tiled: bool,
+    exr_tile_size: (usize, usize),
+    mipmaps: bool,
+    with_mask: bool,
+    colormap: Option<colormap::Colormap>,
+    cancel: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    threads: Option<usize>,
+    progress: Option<&dyn Fn(f32)>,
+) -> Result<PathBuf> {
+    use exr::prelude::{AttributeValue, Encoding, Image, Layer, LayerAttributes, SpecificChannels, Text, Vec2};
+
+    let in_image_path = in_image_path.as_ref();
+    let export_dir = export_dir.as_ref();
+
+    let export_started_at = std::time::Instant::now();
+
+    let dataset = Dataset::open(in_image_path)?;
+    let (full_w, full_h) = dataset.raster_size();
+    let (crop_x, crop_y, raster_w, raster_h) = match bbox {
+        Some((min_x, min_y, max_x, max_y)) => {
+            let min_x = min_x.min(full_w.saturating_sub(1));
+            let min_y = min_y.min(full_h.saturating_sub(1));
+            let max_x = max_x.clamp(min_x + 1, full_w);
+            let max_y = max_y.clamp(min_y + 1, full_h);
+            info!(
+                "cropping to bbox ({min_x}, {min_y})-({max_x}, {max_y}) of {full_w}x{full_h}"
+            );
+            (min_x, min_y, max_x - min_x, max_y - min_y)
+        }
+        None => (0, 0, full_w, full_h),
+    };
+    let band_count = dataset.raster_count();
+
+    if let BandSelection::Single(index) = band_selection {
+        if index == 0 || index > band_count {
+            return Err(eyre!(
+                "band {index} is out of range; dataset has {band_count} band(s)"
+            ));
+        }
+    }
+
+    let elevation_offset = if use_metadata_offset {
+        match detect_elevation_offset(&dataset)? {
+            Some((key, value)) => {
+                info!("applying elevation offset {value} from metadata key {key}");
+                value
+            }
+            None => {
+                log::warn!("--use-metadata-offset set but no recognized elevation-offset metadata found");
+                0.0
+            }
+        }
+    } else {
+        0.0
+    };
+
+    info!("raster size: {}x{}", raster_w, raster_h);
+    info!("band count: {}", band_count);
+
+    if output_format == DtmOutputFormat::Png16 && !normalize {
+        return Err(eyre!(
+            "--format png16 requires --normalize, since a 16-bit PNG can't hold a raw elevation's \
+             full range -- pass --normalize or export as exr/geotiff instead"
+        ));
+    }
+
+    if normalize_per_tile {
+        log::warn!(
+            "--normalize-per-tile produces non-physical output (visible tile seams); use only for diagnosing tiling/normalization issues"
+        );
+    }
+
+    let (region_size_w, region_size_h) = tile_region_size(raster_w, raster_h, window_scale_factor);
+
+    let pool = match threads {
+        Some(n) if n > 0 => Some(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map_err(|err| eyre!("failed to build thread pool with {n} threads: {err}"))?,
+        ),
+        _ => None,
+    };
+
+    // One GDAL band handle + its stats per band this export actually writes,
+    // gathered upfront so the row cache below never has to reopen the
+    // dataset mid-stream just to learn a band's min/max.
+    let mut active_bands: Vec<usize> = Vec::new();
+    let mut band_stats: Vec<Option<(f64, f64)>> = Vec::new();
+    let mut band_nodata: Vec<Option<f64>> = Vec::new();
+
+    // Band 1's stats, captured here and reused for `write_aux_xml` below so
+    // that enabling both `normalize` and `write_aux_xml` doesn't pay for a
+    // second full-raster min/max pass.
+    let mut band_one_stats: Option<(f64, f64)> = None;
+
+    for band_index in 1..=band_count {
+        if let BandSelection::Single(selected) = band_selection {
+            if band_index != selected {
+                continue;
+            }
+        }
+
+        if band_selection == BandSelection::All && band_index > 3 {
+            log::warn!(
+                "dataset has {band_count} bands; only the first 3 are mapped to R/G/B channels"
+            );
+            break;
+        }
+
+        let band = dataset.rasterband(band_index)?;
+        check_supported_pixel_type(&band)?;
+        info!("band {band_index} source type: {:?} (read as f32)", band.band_type());
+
+        let stats = if let Some(range) = manual_range {
+            Some(range)
+        } else if let Some((low, high)) = percentile_range {
+            Some(band_percentile_range(&band, crop_x, crop_y, raster_w, raster_h, elevation_offset, low, high)?)
+        } else if normalize && !normalize_per_tile {
+            // Whole raster, not just the bbox crop -- see `bbox` above.
+            let (min, max) = band_min_max_excluding_nodata(&band, 0, 0, full_w, full_h)?;
+            Some((min + elevation_offset, max + elevation_offset))
+        } else {
+            None
+        };
+
+        if band_index == 1 {
+            band_one_stats = stats;
+        }
+
+        active_bands.push(band_index);
+        band_stats.push(stats);
+        band_nodata.push(band.no_data_value());
+    }
+
+    let writes_normalized = normalize || normalize_per_tile || manual_range.is_some() || percentile_range.is_some();
+    let graticule_geo_transform = match graticule_spacing {
+        Some(_) if writes_normalized => Some(cropped_geo_transform(&dataset.geo_transform()?, crop_x, crop_y)),
+        _ => None,
+    };
+
+    let tile_row_starts: Vec<usize> = (0..raster_h).step_by(region_size_h).collect();
+    let total_rows = tile_row_starts.len();
+    let completed_rows = std::sync::Mutex::new(0usize);
+
+    // Computes every active band's normalized, flip/nodata/graticule-aware
+    // RGB triplet for one row of tiles (`region_size_h` rows tall, less for
+    // the final row), parallelizing across `region_size_w`-wide column
+    // tiles within the row. Kept to one row of tiles at a time so the cache
+    // below never holds more than `raster_w * region_size_h` pixels.
+    let compute_row = |row_start: usize| -> Result<(Vec<[f32; 3]>, Vec<f32>)> {
+        if cancel.as_ref().map(|c| c.load(std::sync::atomic::Ordering::Relaxed)).unwrap_or(false) {
+            return Err(eyre!("export cancelled"));
+        }
+
+        let row_height = region_size_h.min(raster_h - row_start);
+        let mut row_buf = vec![[0.0f32; 3]; raster_w * row_height];
+        let mut alpha_buf = vec![1.0f32; raster_w * row_height];
+
+        let x_offsets: Vec<usize> = (0..raster_w).step_by(region_size_w).collect();
+
+        // Each column tile opens its own `Dataset`/`RasterBand` handles,
+        // since a GDAL dataset isn't `Sync` and can't be shared across the
+        // rayon worker threads reading tiles concurrently.
+        let compute_column_tile = |&x_offset: &usize| -> Result<(usize, usize, Vec<[f32; 3]>, Vec<f32>)> {
+            let tile_dataset = Dataset::open(in_image_path)?;
+            let tile_w = region_size_w.min(raster_w - x_offset);
+            let mut local = vec![[0.0f32; 3]; tile_w * row_height];
+            let mut local_alpha = vec![1.0f32; tile_w * row_height];
+
+            // Expanded by `tile_overlap` (or `fill_voids`, whichever needs
+            // more context) pixels on each side (clamped at the raster's
+            // edges) so `resample`/`fill_voids_in_place` have real source
+            // pixels to draw on near a tile boundary, then trimmed back
+            // down to exactly `tile_w` x `row_height` below -- everything
+            // downstream of this read sees the same interior-only buffer as
+            // when both are 0.
+            let halo_radius = tile_overlap.max(fill_voids.unwrap_or(0));
+            let halo_left = halo_radius.min(x_offset);
+            let halo_right = halo_radius.min(raster_w.saturating_sub(x_offset + tile_w));
+            let halo_top = halo_radius.min(row_start);
+            let halo_bottom = halo_radius.min(raster_h.saturating_sub(row_start + row_height));
+            let read_w = tile_w + halo_left + halo_right;
+            let read_h = row_height + halo_top + halo_bottom;
+
+            for (band_slot, &band_index) in active_bands.iter().enumerate() {
+                let tile_band = tile_dataset.rasterband(band_index)?;
+                let haloed = tile_band.read_as::<f32>(
+                    ((x_offset - halo_left + crop_x) as isize, (row_start - halo_top + crop_y) as isize),
+                    (read_w, read_h),
+                    (read_w, read_h),
+                    Some(resample),
+                )?;
+
+                let mut haloed_data = haloed.data().to_vec();
+                if let (Some(max_gap), Some(nodata)) = (fill_voids, band_nodata[band_slot]) {
+                    fill_voids_in_place(&mut haloed_data, read_w, read_h, nodata, max_gap);
+                }
+
+                let buffer: Vec<f32> = if halo_left == 0 && halo_right == 0 && halo_top == 0 && halo_bottom == 0 {
+                    haloed_data
+                } else {
+                    let mut interior = vec![0.0f32; tile_w * row_height];
+                    for local_y in 0..row_height {
+                        let src_start = (local_y + halo_top) * read_w + halo_left;
+                        let dst_start = local_y * tile_w;
+                        interior[dst_start..dst_start + tile_w]
+                            .copy_from_slice(&haloed_data[src_start..src_start + tile_w]);
+                    }
+                    interior
+                };
+
+                let tile_stats = if normalize_per_tile && manual_range.is_none() && percentile_range.is_none() {
+                    let nodata = band_nodata[band_slot];
+                    let valid_values = buffer.iter().filter(|&&v| {
+                        nodata.map(|nd| (v as f64 - nd).abs() >= f64::EPSILON).unwrap_or(true)
+                    });
+                    let min = valid_values.clone().cloned().fold(f32::INFINITY, f32::min);
+                    let max = valid_values.cloned().fold(f32::NEG_INFINITY, f32::max);
+                    Some((min as f64, max as f64))
+                } else {
+                    band_stats[band_slot]
+                };
+
+                // Checked explicitly (rather than via `checked_map_range`) because a
+                // flat tile on this path writes a fixed 0.0 for every pixel instead
+                // of `None` -- a flat tile/band is an expected input (e.g. a
+                // water-body mask or a clipped-flat DTM edge), not a per-pixel error
+                // to fall back from, so normalization stays fully deterministic
+                // here rather than skipping pixels or propagating a NaN.
+                let is_flat = tile_stats.map(|(min, max)| (max - min).abs() < f64::EPSILON).unwrap_or(false);
+                if is_flat {
+                    log::warn!(
+                        "band {band_index} has a flat value range (min == max); writing 0.0 instead of dividing by zero"
+                    );
+                }
+
+                for (i, &raw_value) in buffer.iter().enumerate() {
+                    let value = (raw_value + elevation_offset as f32) * vertical_exaggeration;
+                    let local_x = i % tile_w;
+                    let local_y = i / tile_w;
+
+                    let mapped = match tile_stats {
+                        Some((min, max)) => {
+                            let normalized = if is_flat {
+                                0.0
+                            } else {
+                                map_range(value as f64, (min, max), (0.0, 1.0)) as f32
+                            };
+                            let normalized = if manual_range.is_some() || percentile_range.is_some() {
+                                normalized.clamp(0.0, 1.0)
+                            } else {
+                                normalized
+                            };
+                            let normalized = apply_tonemap(normalized, tonemap, gamma);
+                            if colorspace == ColorSpace::Srgb {
+                                srgb_encode(normalized)
+                            } else {
+                                normalized
+                            }
+                        }
+                        None => value,
+                    };
+
+                    let is_nodata = band_nodata[band_slot]
+                        .map(|nodata| (raw_value as f64 - nodata).abs() < f64::EPSILON)
+                        .unwrap_or(false);
+
+                    let nodata_value = match nodata_as {
+                        NodataAs::Zero => 0.0,
+                        NodataAs::Nan => f32::NAN,
+                    };
+
+                    let out_idx = local_y * tile_w + local_x;
+                    match band_selection {
+                        BandSelection::Single(_) => {
+                            local[out_idx] = match (is_nodata, nodata_color) {
+                                (true, Some(color)) => color,
+                                (true, None) => [nodata_value; 3],
+                                _ => match colormap {
+                                    Some(map) => colormap::apply(map, mapped).0,
+                                    None => [mapped, mapped, mapped],
+                                },
+                            };
+                        }
+                        BandSelection::All => {
+                            let channel = band_index - 1;
+                            local[out_idx][channel] = if is_nodata { nodata_value } else { mapped };
+                        }
+                    }
+
+                    if is_nodata {
+                        local_alpha[out_idx] = 0.0;
+                    }
+                }
+            }
+
+            Ok((x_offset, tile_w, local, local_alpha))
+        };
+
+        let column_tiles: Result<Vec<(usize, usize, Vec<[f32; 3]>, Vec<f32>)>> = match &pool {
+            Some(pool) => pool.install(|| x_offsets.par_iter().map(compute_column_tile).collect()),
+            None => x_offsets.par_iter().map(compute_column_tile).collect(),
+        };
+
+        for (x_offset, tile_w, local, local_alpha) in column_tiles? {
+            for local_y in 0..row_height {
+                for local_x in 0..tile_w {
+                    let src_idx = local_y * tile_w + local_x;
+                    let dst_idx = local_y * raster_w + x_offset + local_x;
+                    row_buf[dst_idx] = local[src_idx];
+                    alpha_buf[dst_idx] = local_alpha[src_idx];
+                }
+            }
+        }
+
+        if let Some(geo_transform) = &graticule_geo_transform {
+            for local_y in 0..row_height {
+                for x in 0..raster_w {
+                    let py = row_start + local_y;
+                    let idx = local_y * raster_w + x;
+                    row_buf[idx] = graticule_blend(
+                        row_buf[idx],
+                        x,
+                        py,
+                        geo_transform,
+                        graticule_spacing.unwrap_or(1.0),
+                        graticule_color,
+                        graticule_opacity,
+                    );
+                }
+            }
+        }
+
+        *completed_rows.lock().unwrap() += 1;
+        if let Some(progress) = progress {
+            progress(*completed_rows.lock().unwrap() as f32 / total_rows as f32);
+        }
+
+        Ok((row_buf, alpha_buf))
+    };
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let derived_stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let stem = match output_name {
+        Some(name) => std::ffi::OsString::from(name),
+        None => derived_stem.to_os_string(),
+    };
+    let extension = match output_format {
+        DtmOutputFormat::Exr => "exr",
+        DtmOutputFormat::GeoTiff => "tif",
+        DtmOutputFormat::Png16 => "png",
+    };
+    let output_path = export_dir.join(&stem).with_extension(extension);
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    match output_format {
+        DtmOutputFormat::Exr => {
+            // The row cache holds at most one `region_size_h`-tall band of
+            // the *output* image, keyed by its source-space start row
+            // (flips are applied when a pixel is read out of the cache, not
+            // when it's filled). `exr`'s pixel-pull writer walks the image
+            // roughly top-to-bottom, so in practice each row band is
+            // computed once; an out-of-order request just forces a
+            // recompute instead of producing wrong pixels.
+            let row_cache: std::sync::Mutex<Option<(usize, Vec<[f32; 3]>, Vec<f32>)>> = std::sync::Mutex::new(None);
+
+            let fetch = |position: Vec2<usize>| -> (usize, [f32; 3], f32) {
+                let out_x = position.0;
+                let out_y = position.1;
+                let source_x = if flip_x { raster_w - 1 - out_x } else { out_x };
+                let source_y = if flip_y { raster_h - 1 - out_y } else { out_y };
+                let row_start = (source_y / region_size_h) * region_size_h;
+
+                let mut cache = row_cache.lock().unwrap();
+                if cache.as_ref().map(|(start, _, _)| *start) != Some(row_start) {
+                    let (row_buf, alpha_buf) = compute_row(row_start).expect("failed to read a row of tiles while streaming EXR output");
+                    *cache = Some((row_start, row_buf, alpha_buf));
+                }
+
+                let (start, row_buf, alpha_buf) = cache.as_ref().unwrap();
+                let local_y = source_y - start;
+                let idx = local_y * raster_w + source_x;
+                (idx, row_buf[idx], alpha_buf[idx])
+            };
+
+            let get_pixel = |position: Vec2<usize>| -> (f32, f32, f32) {
+                let (_, [r, g, b], _) = fetch(position);
+                (r, g, b)
+            };
+
+            let get_pixel_with_mask = |position: Vec2<usize>| -> (f32, f32, f32, f32) {
+                let (_, [r, g, b], a) = fetch(position);
+                (r, g, b, a)
+            };
+
+            let get_pixel_y = |position: Vec2<usize>| -> f32 {
+                let (_, [r, _, _], _) = fetch(position);
+                r
+            };
+
+            // A colormap needs distinct per-channel color, and
+            // `BandSelection::All` already uses the channels for separate
+            // bands -- `channels` only applies to the plain single-band
+            // grayscale case, so fall back to RGB rather than silently
+            // discarding either.
+            let channels = match channels {
+                ExrChannels::Gray if colormap.is_none() && matches!(band_selection, BandSelection::Single(_)) => {
+                    ExrChannels::Gray
+                }
+                ExrChannels::Gray => {
+                    log::warn!(
+                        "--channels gray only applies to a single band without a colormap; writing RGB instead"
+                    );
+                    ExrChannels::Rgb
+                }
+                ExrChannels::Rgb => ExrChannels::Rgb,
+            };
+
+            // Embed the source dataset's georeferencing as custom EXR
+            // attributes, so tools that read them (or a human inspecting
+            // the header) can recover where this terrain sits without the
+            // sidecar world file below.
+            let geo_transform_for_attrs = flipped_geo_transform(
+                &cropped_geo_transform(&dataset.geo_transform()?, crop_x, crop_y),
+                raster_w,
+                raster_h,
+                flip_x,
+                flip_y,
+            );
+            let crs_wkt = dataset.spatial_ref().ok().and_then(|srs| srs.to_wkt().ok());
+
+            let mut layer_attributes = LayerAttributes::named(stem.to_string_lossy().into_owned());
+            layer_attributes.other.insert(
+                Text::from("GDAL_GeoTransform"),
+                AttributeValue::Text(Text::from(
+                    geo_transform_for_attrs
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )),
+            );
+            if let Some(wkt) = crs_wkt {
+                layer_attributes
+                    .other
+                    .insert(Text::from("GDAL_SpatialRef"), AttributeValue::Text(Text::from(wkt)));
+            }
+
+            // `normalize` maps elevation into [0, 1] before it's written, which
+            // is otherwise a one-way trip -- embed the range it normalized
+            // against (and the band's unit, if the dataset declares one) so a
+            // downstream reader can invert it with `denormalize` instead of
+            // having to re-derive the original min/max itself.
+            if normalize {
+                if let Some((min, max)) = band_one_stats {
+                    layer_attributes.other.insert(
+                        Text::from("DTM_ElevationMin"),
+                        AttributeValue::Text(Text::from(min.to_string())),
+                    );
+                    layer_attributes.other.insert(
+                        Text::from("DTM_ElevationMax"),
+                        AttributeValue::Text(Text::from(max.to_string())),
+                    );
+
+                    let unit = dataset.rasterband(1)?.unit();
+                    if !unit.is_empty() {
+                        layer_attributes
+                            .other
+                            .insert(Text::from("DTM_ElevationUnit"), AttributeValue::Text(Text::from(unit)));
+                    }
+                }
+            }
+
+            if mipmaps {
+                use exr::prelude::{AnyChannel, AnyChannels, FlatSamples, ImageAttributes};
+
+                // A mip pyramid needs the complete base level to downsample
+                // from, so -- unlike the streaming branches below -- this
+                // path materializes the whole image in memory up front
+                // instead of pulling one row band at a time.
+                let mut base_rgb = vec![[0.0f32; 3]; raster_w * raster_h];
+                let mut base_alpha = vec![1.0f32; raster_w * raster_h];
+                for y in 0..raster_h {
+                    for x in 0..raster_w {
+                        let (r, g, b, a) = get_pixel_with_mask(Vec2(x, y));
+                        let idx = y * raster_w + x;
+                        base_rgb[idx] = [r, g, b];
+                        base_alpha[idx] = a;
+                    }
+                }
+
+                let mut levels = vec![(raster_w, raster_h, base_rgb, base_alpha)];
+                while {
+                    let (w, h, _, _) = levels.last().unwrap();
+                    *w > 1 || *h > 1
+                } {
+                    let next_level = {
+                        let (w, h, rgb, alpha) = levels.last().unwrap();
+                        downsample_mip_level(rgb, alpha, *w, *h)
+                    };
+                    levels.push(next_level);
+                }
+                info!(
+                    "building {} mip levels ({raster_w}x{raster_h} down to 1x1)",
+                    levels.len()
+                );
+
+                let layers: Vec<_> = levels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(level, (w, h, rgb, alpha))| {
+                        let channel_list: smallvec::SmallVec<[AnyChannel<FlatSamples>; 4]> = if with_mask {
+                            smallvec::smallvec![
+                                AnyChannel::new("R", FlatSamples::F32(rgb.iter().map(|p| p[0]).collect())),
+                                AnyChannel::new("G", FlatSamples::F32(rgb.iter().map(|p| p[1]).collect())),
+                                AnyChannel::new("B", FlatSamples::F32(rgb.iter().map(|p| p[2]).collect())),
+                                AnyChannel::new("A", FlatSamples::F32(alpha)),
+                            ]
+                        } else if channels == ExrChannels::Gray {
+                            smallvec::smallvec![AnyChannel::new(
+                                "Y",
+                                FlatSamples::F32(rgb.iter().map(|p| p[0]).collect())
+                            )]
+                        } else {
+                            smallvec::smallvec![
+                                AnyChannel::new("R", FlatSamples::F32(rgb.iter().map(|p| p[0]).collect())),
+                                AnyChannel::new("G", FlatSamples::F32(rgb.iter().map(|p| p[1]).collect())),
+                                AnyChannel::new("B", FlatSamples::F32(rgb.iter().map(|p| p[2]).collect())),
+                            ]
+                        };
+
+                        let mut level_attributes =
+                            LayerAttributes::named(format!("{}.mip{level}", stem.to_string_lossy()));
+                        level_attributes.other = layer_attributes.other.clone();
+
+                        Layer::new(
+                            (w, h),
+                            level_attributes,
+                            exr_encoding_for(exr_compression, exr_tiled.then_some(exr_tile_size)),
+                            AnyChannels::sort(channel_list),
+                        )
+                    })
+                    .collect();
+
+                write_atomically(&output_path, |tmp_path| {
+                    Ok(Image::from_layers(ImageAttributes::default(), layers).write().to_file(tmp_path)?)
+                })?;
+            } else if with_mask {
+                let layer = Layer::new(
+                    (raster_w, raster_h),
+                    layer_attributes,
+                    exr_encoding_for(exr_compression, exr_tiled.then_some(exr_tile_size)),
+                    SpecificChannels::rgba(get_pixel_with_mask),
+                );
+
+                write_atomically(&output_path, |tmp_path| {
+                    Ok(Image::from_layer(layer).write().to_file(tmp_path)?)
+                })?;
+            } else if channels == ExrChannels::Gray {
+                let layer = Layer::new(
+                    (raster_w, raster_h),
+                    layer_attributes,
+                    exr_encoding_for(exr_compression, exr_tiled.then_some(exr_tile_size)),
+                    SpecificChannels::build().with_channel("Y").with_pixel_fn(get_pixel_y),
+                );
+
+                write_atomically(&output_path, |tmp_path| {
+                    Ok(Image::from_layer(layer).write().to_file(tmp_path)?)
+                })?;
+            } else {
+                let layer = Layer::new(
+                    (raster_w, raster_h),
+                    layer_attributes,
+                    exr_encoding_for(exr_compression, exr_tiled.then_some(exr_tile_size)),
+                    SpecificChannels::rgb(get_pixel),
+                );
+
+                write_atomically(&output_path, |tmp_path| {
+                    Ok(Image::from_layer(layer).write().to_file(tmp_path)?)
+                })?;
+            }
+        }
+
+        DtmOutputFormat::GeoTiff => {
+            let output_band_count = active_bands.len();
+            write_atomically(&output_path, |tmp_path| {
+                let tiff_driver = DriverManager::get_driver_by_name("GTiff")?;
+                let mut out_dataset = tiff_driver.create_with_band_type::<f32, _>(
+                    tmp_path,
+                    raster_w,
+                    raster_h,
+                    output_band_count,
+                )?;
+                out_dataset.set_geo_transform(&flipped_geo_transform(
+                    &cropped_geo_transform(&dataset.geo_transform()?, crop_x, crop_y),
+                    raster_w,
+                    raster_h,
+                    flip_x,
+                    flip_y,
+                ))?;
+                out_dataset.set_projection(&dataset.projection())?;
+
+                for &row_start in &tile_row_starts {
+                    let row_height = region_size_h.min(raster_h - row_start);
+                    let (row_buf, _alpha_buf) = compute_row(row_start)?;
+
+                    for channel in 0..output_band_count {
+                        let mut line = vec![0.0f32; raster_w * row_height];
+                        for local_y in 0..row_height {
+                            let dest_local_y = if flip_y { row_height - 1 - local_y } else { local_y };
+                            for source_x in 0..raster_w {
+                                let dest_x = if flip_x { raster_w - 1 - source_x } else { source_x };
+                                line[dest_local_y * raster_w + dest_x] = row_buf[local_y * raster_w + source_x][channel];
+                            }
+                        }
+
+                        let dest_row_start = if flip_y { raster_h - row_start - row_height } else { row_start };
+                        let mut dst_band = out_dataset.rasterband(channel + 1)?;
+                        dst_band.write(
+                            (0, dest_row_start as isize),
+                            (raster_w, row_height),
+                            &Buffer::new((raster_w, row_height), line),
+                        )?;
+                    }
+                }
+
+                if build_overviews {
+                    let levels: Vec<i32> = OVERVIEW_LEVELS
+                        .iter()
+                        .copied()
+                        .filter(|&level| raster_w / level as usize >= 1 && raster_h / level as usize >= 1)
+                        .collect();
+                    out_dataset.build_overviews(overview_resample, &levels, &[])?;
+                    info!("built overview levels {levels:?} ({overview_resample})");
+                }
+
+                Ok(())
+            })?;
+        }
+
+        DtmOutputFormat::Png16 => {
+            let mut pixels = vec![0u16; raster_w * raster_h];
+            for &row_start in &tile_row_starts {
+                let row_height = region_size_h.min(raster_h - row_start);
+                let (row_buf, _alpha_buf) = compute_row(row_start)?;
+
+                for local_y in 0..row_height {
+                    for source_x in 0..raster_w {
+                        let dest_x = if flip_x { raster_w - 1 - source_x } else { source_x };
+                        let source_y = row_start + local_y;
+                        let dest_y = if flip_y { raster_h - 1 - source_y } else { source_y };
+
+                        let value = row_buf[local_y * raster_w + source_x][0].clamp(0.0, 1.0);
+                        pixels[dest_y * raster_w + dest_x] = (value * 65535.0).round() as u16;
+                    }
+                }
+            }
+
+            write_atomically(&output_path, |tmp_path| {
+                write_grayscale_png(
+                    tmp_path,
+                    raster_w as u32,
+                    raster_h as u32,
+                    &pixels,
+                    PngBitDepth::Sixteen,
+                    PngMode::Visualization,
+                    colorspace,
+                )
+            })?;
+        }
+    }
+
+    if write_aux_xml {
+        let (min, max) = match band_one_stats {
+            Some(stats) => stats,
+            None => band_min_max_excluding_nodata(&dataset.rasterband(1)?, crop_x, crop_y, raster_w, raster_h)?,
+        };
+        write_pam_aux_xml(&output_path, min, max)?;
+    }
+
+    if world_file {
+        write_world_file(
+            &output_path,
+            &flipped_geo_transform(
+                &cropped_geo_transform(&dataset.geo_transform()?, crop_x, crop_y),
+                raster_w,
+                raster_h,
+                flip_x,
+                flip_y,
+            ),
+        )?;
+    }
+
+    if stats_json {
+        let normalization_mode = if manual_range.is_some() {
+            "manual"
+        } else if percentile_range.is_some() {
+            "percentile"
+        } else if normalize_per_tile {
+            "per_tile"
+        } else if normalize {
+            "auto"
+        } else {
+            "none"
+        };
+
+        write_stats_json(
+            &output_path,
+            &in_image_path.to_path_buf(),
+            &dataset,
+            raster_w,
+            raster_h,
+            window_scale_factor,
+            normalization_mode,
+            band_one_stats,
+            &active_bands,
+            &band_stats,
+            &band_nodata,
+        )?;
+    }
+
+    info!("wrote {}", output_path.display());
+
+    let elapsed = export_started_at.elapsed().as_secs_f64();
+    let mpix_per_sec = if elapsed > 0.0 {
+        (raster_w * raster_h) as f64 / 1_000_000.0 / elapsed
+    } else {
+        0.0
+    };
+    info!("exported {raster_w}x{raster_h} in {elapsed:.1}s ({mpix_per_sec:.1} MPix/s)");
+
+    Ok(output_path)
+}
+
+/// Reads `in_image_path`'s first band, normalizes it to `bit_depth`, and
+/// writes a grayscale PNG into `export_dir`, named after the input file's
+/// stem.
+///
+/// `mode` controls only the file's gAMA/sRGB chunk, not the pixel values:
+/// [`PngMode::Visualization`] tags the PNG as sRGB (or linear-gamma, per
+/// `colorspace`) so viewers and image-loading libraries all decode it the
+/// same way. [`PngMode::Data`] writes the identical quantized pixels but
+/// omits the chunk entirely, since a reader pulling the values back out as
+/// data would otherwise have a color-managed viewer silently reinterpret
+/// them.
+pub fn export_dtm_to_png(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    window_scale_factor: usize,
+    overwrite: bool,
+    bit_depth: PngBitDepth,
+    mode: PngMode,
+    colorspace: ColorSpace,
+) -> Result<PathBuf> {
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+
+    let (min, max) = band_min_max_excluding_nodata(&band, 0, 0, raster_w, raster_h)?;
+
+    let (region_size_w, region_size_h) = tile_region_size(raster_w, raster_h, window_scale_factor);
+
+    let max_value = match bit_depth {
+        PngBitDepth::Eight => u8::MAX as f64,
+        PngBitDepth::Sixteen => u16::MAX as f64,
+    };
+
+    let mut pixels = vec![0u16; raster_w * raster_h];
+
+    for y_offset in (0..raster_h).step_by(region_size_h) {
+        for x_offset in (0..raster_w).step_by(region_size_w) {
+            let buffer = band.read_as::<f32>(
+                (x_offset as isize, y_offset as isize),
+                (region_size_w, region_size_h),
+                (region_size_w, region_size_h),
+                Some(ResampleAlg::Bilinear),
+            )?;
+
+            for (i, &raw_value) in buffer.data().iter().enumerate() {
+                let px = x_offset + i % region_size_w;
+                let py = y_offset + i / region_size_w;
+
+                let normalized = checked_map_range(raw_value as f64, (min, max), (0.0, 1.0)).unwrap_or(0.0);
+                pixels[py * raster_w + px] = (normalized.clamp(0.0, 1.0) * max_value).round() as u16;
+            }
+        }
+    }
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("png");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    write_atomically(&output_path, |tmp_path| {
+        write_grayscale_png(tmp_path, raster_w as u32, raster_h as u32, &pixels, bit_depth, mode, colorspace)
+    })?;
+
+    info!("wrote {}", output_path.display());
+
+    Ok(output_path)
+}
+
+/// Reads band 1 of `in_image_path` and writes a grayscale hillshade: the
+/// Lambertian shading of the terrain's surface normal under a sun at
+/// `azimuth_deg`/`altitude_deg`, via [`terrain::horn_gradient`] and
+/// [`terrain::hillshade_from_gradient`].
+///
+/// Reads the whole band into memory up front, since Horn's 3x3 gradient
+/// needs every pixel's neighbors -- unlike the streaming exporters above,
+/// there's no way to visit one tile at a time without re-reading overlap at
+/// every tile boundary.
+///
+/// `output_format` picks the encoder; only [`DtmOutputFormat::Exr`] (written
+/// as a flat grayscale RGB triplet) and [`DtmOutputFormat::Png16`] are
+/// supported today.
+pub fn export_hillshade(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    overwrite: bool,
+    azimuth_deg: f32,
+    altitude_deg: f32,
+    z_factor: f32,
+    output_format: DtmOutputFormat,
+) -> Result<PathBuf> {
+    use exr::prelude::{Encoding, Image, Layer, LayerAttributes, SpecificChannels, Vec2};
+
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+
+    let geo_transform = dataset.geo_transform()?;
+    let pixel_size_x = geo_transform[1].abs();
+    let pixel_size_y = geo_transform[5].abs();
+
+    let heights = band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+    let heights = heights.data();
+
+    let mut shaded = vec![0.0f32; raster_w * raster_h];
+    for y in 0..raster_h {
+        for x in 0..raster_w {
+            let (dzdx, dzdy) = terrain::horn_gradient(heights, raster_w, raster_h, x, y, pixel_size_x, pixel_size_y);
+            shaded[y * raster_w + x] =
+                terrain::hillshade_from_gradient(dzdx, dzdy, azimuth_deg as f64, altitude_deg as f64, z_factor as f64);
+        }
+    }
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+
+    match output_format {
+        DtmOutputFormat::Exr => {
+            let output_path = export_dir.join(stem).with_extension("exr");
+            if !overwrite && !confirm_overwrite(&output_path)? {
+                return Err(DtmExportError::OutputExists(output_path.clone()).into());
+            }
+
+            let get_pixel = |position: Vec2<usize>| -> (f32, f32, f32) {
+                let value = shaded[position.1 * raster_w + position.0];
+                (value, value, value)
+            };
+
+            let layer = Layer::new(
+                (raster_w, raster_h),
+                LayerAttributes::named(stem.to_string_lossy().into_owned()),
+                Encoding::FAST_LOSSLESS,
+                SpecificChannels::rgb(get_pixel),
+            );
+
+            write_atomically(&output_path, |tmp_path| Ok(Image::from_layer(layer).write().to_file(tmp_path)?))?;
+            info!("wrote {}", output_path.display());
+            Ok(output_path)
+        }
+        DtmOutputFormat::Png16 => {
+            let output_path = export_dir.join(stem).with_extension("png");
+            if !overwrite && !confirm_overwrite(&output_path)? {
+                return Err(DtmExportError::OutputExists(output_path.clone()).into());
+            }
+
+            let pixels: Vec<u16> = shaded.iter().map(|&v| (v.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16).collect();
+
+            write_atomically(&output_path, |tmp_path| {
+                write_grayscale_png(tmp_path, raster_w as u32, raster_h as u32, &pixels, PngBitDepth::Sixteen, PngMode::Visualization, ColorSpace::Linear)
+            })?;
+            info!("wrote {}", output_path.display());
+            Ok(output_path)
+        }
+        DtmOutputFormat::GeoTiff => Err(eyre!("hillshade output doesn't support GeoTIFF yet; use --format exr or png16")),
+    }
+}
+
+/// Shared loop behind [`export_slope`] and [`export_aspect`]: reads
+/// band 1 of `in_image_path` fully into memory (Horn's 3x3 gradient needs
+/// every pixel's neighbors, same as [`export_hillshade`]), evaluates
+/// `derive` at every pixel, and writes the result as `output_format`.
+///
+/// A pixel is no-data if any of its 3x3 Horn neighbors are, so a ridge right
+/// next to a void doesn't get a gradient computed partly from made-up
+/// terrain; no-data pixels write `0.0` in both EXR and PNG16.
+///
+/// `normalize_for_png` maps a raw value (e.g. degrees or percent) to `[0, 1]`
+/// for the PNG16 path, which has nowhere to store out-of-range floats; the
+/// EXR path always writes the raw value, unclamped, since it has no such
+/// limit.
+fn export_terrain_derivative(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    overwrite: bool,
+    output_format: DtmOutputFormat,
+    extension: &str,
+    derive: impl Fn(f64, f64) -> f32,
+    normalize_for_png: impl Fn(f32) -> f32,
+) -> Result<PathBuf> {
+    use exr::prelude::{Encoding, Image, Layer, LayerAttributes, SpecificChannels, Vec2};
+
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+    let nodata = band.no_data_value();
+
+    let geo_transform = dataset.geo_transform()?;
+    let pixel_size_x = geo_transform[1].abs();
+    let pixel_size_y = geo_transform[5].abs();
+
+    let heights = band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+    let heights = heights.data();
+
+    let is_nodata = |x: usize, y: usize| -> bool {
+        nodata.map(|nd| (heights[y * raster_w + x] as f64 - nd).abs() < f64::EPSILON).unwrap_or(false)
+    };
+
+    let mut values = vec![0.0f32; raster_w * raster_h];
+    for y in 0..raster_h {
+        for x in 0..raster_w {
+            let neighbor_is_nodata = (-1isize..=1).any(|dy| {
+                (-1isize..=1).any(|dx| {
+                    let sx = (x as isize + dx).clamp(0, raster_w as isize - 1) as usize;
+                    let sy = (y as isize + dy).clamp(0, raster_h as isize - 1) as usize;
+                    is_nodata(sx, sy)
+                })
+            });
+
+            values[y * raster_w + x] = if neighbor_is_nodata {
+                0.0
+            } else {
+                let (dzdx, dzdy) = terrain::horn_gradient(heights, raster_w, raster_h, x, y, pixel_size_x, pixel_size_y);
+                derive(dzdx, dzdy)
+            };
+        }
+    }
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+
+    match output_format {
+        DtmOutputFormat::Exr => {
+            let output_path = export_dir.join(stem).with_extension("exr");
+            if !overwrite && !confirm_overwrite(&output_path)? {
+                return Err(DtmExportError::OutputExists(output_path.clone()).into());
+            }
+
+            let get_pixel = |position: Vec2<usize>| -> (f32, f32, f32) {
+                let value = values[position.1 * raster_w + position.0];
+                (value, value, value)
+            };
+
+            let layer = Layer::new(
+                (raster_w, raster_h),
+                LayerAttributes::named(stem.to_string_lossy().into_owned()),
+                Encoding::FAST_LOSSLESS,
+                SpecificChannels::rgb(get_pixel),
+            );
+
+            write_atomically(&output_path, |tmp_path| Ok(Image::from_layer(layer).write().to_file(tmp_path)?))?;
+            info!("wrote {}", output_path.display());
+            Ok(output_path)
+        }
+        DtmOutputFormat::Png16 => {
+            let output_path = export_dir.join(stem).with_extension(extension);
+            if !overwrite && !confirm_overwrite(&output_path)? {
+                return Err(DtmExportError::OutputExists(output_path.clone()).into());
+            }
+
+            let pixels: Vec<u16> = values
+                .iter()
+                .map(|&v| (normalize_for_png(v).clamp(0.0, 1.0) * u16::MAX as f32).round() as u16)
+                .collect();
+
+            write_atomically(&output_path, |tmp_path| {
+                write_grayscale_png(tmp_path, raster_w as u32, raster_h as u32, &pixels, PngBitDepth::Sixteen, PngMode::Visualization, ColorSpace::Linear)
+            })?;
+            info!("wrote {}", output_path.display());
+            Ok(output_path)
+        }
+        DtmOutputFormat::GeoTiff => Err(eyre!("terrain derivative output doesn't support GeoTIFF yet; use --format exr or png16")),
+    }
+}
+
+/// Reads band 1 of `in_image_path` and writes its per-pixel slope (the angle
+/// of the surface from horizontal, or rise-over-run as a percent -- picked
+/// by `units`), via [`terrain::horn_gradient`] and
+/// [`terrain::slope_from_gradient`].
+///
+/// The EXR path writes the raw slope value (so a Houdini/compositing tool
+/// gets real degrees or percent, not a normalized grayscale); the PNG16 path
+/// normalizes degrees by 90 and percent by 100, clamping anything steeper.
+pub fn export_slope(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    overwrite: bool,
+    units: terrain::SlopeUnits,
+    output_format: DtmOutputFormat,
+) -> Result<PathBuf> {
+    let max_value = match units {
+        terrain::SlopeUnits::Degrees => 90.0,
+        terrain::SlopeUnits::Percent => 100.0,
+    };
+
+    export_terrain_derivative(
+        in_image_path,
+        export_dir,
+        overwrite,
+        output_format,
+        "png",
+        move |dzdx, dzdy| terrain::slope_from_gradient(dzdx, dzdy, units),
+        move |value| value / max_value,
+    )
+}
+
+/// Reads band 1 of `in_image_path` and writes its per-pixel aspect (the
+/// downslope-facing compass direction, 0-360 degrees from north, or `-1.0`
+/// on flat ground), via [`terrain::horn_gradient`] and
+/// [`terrain::aspect_from_gradient`].
+///
+/// The EXR path writes the raw compass degrees (and the `-1.0` flat
+/// sentinel); the PNG16 path normalizes by 360, mapping flat ground to the
+/// same black as a true-north-facing slope since there's no negative value
+/// to spare in an unsigned 16-bit channel.
+pub fn export_aspect(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    overwrite: bool,
+    output_format: DtmOutputFormat,
+) -> Result<PathBuf> {
+    export_terrain_derivative(
+        in_image_path,
+        export_dir,
+        overwrite,
+        output_format,
+        "png",
+        terrain::aspect_from_gradient,
+        |value| value.max(0.0) / 360.0,
+    )
+}
+
+/// Encodes `pixels` (row-major, one sample per source pixel) as a grayscale
+/// PNG at `tmp_path`, tagging the gAMA/sRGB chunk per `mode`/`colorspace`.
+fn write_grayscale_png(
+    tmp_path: &PathBuf,
+    width: u32,
+    height: u32,
+    pixels: &[u16],
+    bit_depth: PngBitDepth,
+    mode: PngMode,
+    colorspace: ColorSpace,
+) -> Result<()> {
+    let file = std::fs::File::create(tmp_path)?;
+    let mut encoder = png::Encoder::new(io::BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+
+    let bytes: Vec<u8> = match bit_depth {
+        PngBitDepth::Eight => {
+            encoder.set_depth(png::BitDepth::Eight);
+            pixels.iter().map(|&v| v as u8).collect()
+        }
+        PngBitDepth::Sixteen => {
+            encoder.set_depth(png::BitDepth::Sixteen);
+            pixels.iter().flat_map(|&v| v.to_be_bytes()).collect()
+        }
+    };
+
+    if mode == PngMode::Visualization {
+        match colorspace {
+            ColorSpace::Srgb => encoder.set_srgb(png::SrgbRenderingIntent::Perceptual),
+            ColorSpace::Linear => encoder.set_source_gamma(png::ScaledFloat::new(1.0)),
+        }
+    }
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&bytes)?;
+    Ok(())
+}
+
+/// Blends `pixel` with `color` at `opacity` if `(px, py)` falls on a
+/// coordinate-gridline spaced `spacing` CRS units apart, per `geo_transform`,
+/// for visually verifying georeferencing. Never touches raw elevation data --
+/// only called for normalized visualization output.
+/// Used per-pixel while streaming so the graticule overlay doesn't need the
+/// whole output image in memory.
+#[allow(clippy::too_many_arguments)]
+fn graticule_blend(
+    pixel: [f32; 3],
+    px: usize,
+    py: usize,
+    geo_transform: &[f64; 6],
+    spacing: f64,
+    color: [f32; 3],
+    opacity: f32,
+) -> [f32; 3] {
+    let world_x = geo_transform[0] + px as f64 * geo_transform[1] + py as f64 * geo_transform[2];
+    let world_y = geo_transform[3] + px as f64 * geo_transform[4] + py as f64 * geo_transform[5];
+
+    let pixel_span_x = geo_transform[1].abs().max(f64::EPSILON);
+    let pixel_span_y = geo_transform[5].abs().max(f64::EPSILON);
+
+    let on_vertical_line = (world_x.rem_euclid(spacing)) < pixel_span_x;
+    let on_horizontal_line = (world_y.rem_euclid(spacing)) < pixel_span_y;
+
+    if on_vertical_line || on_horizontal_line {
+        [
+            pixel[0] * (1.0 - opacity) + color[0] * opacity,
+            pixel[1] * (1.0 - opacity) + color[1] * opacity,
+            pixel[2] * (1.0 - opacity) + color[2] * opacity,
+        ]
+    } else {
+        pixel
+    }
+}
+
+/// Writes a GDAL PAM-format `.aux.xml` sidecar next to `output_path`,
+/// carrying the band-1 statistics so QGIS and other GDAL consumers don't
+/// have to recompute them (slow on large rasters) when opening the output.
+///
+/// Only `STATISTICS_MINIMUM`/`STATISTICS_MAXIMUM` are populated; mean/stddev
+/// aren't tracked by the exporter today, so they're left out rather than
+/// written as made-up values.
+fn write_pam_aux_xml(output_path: &PathBuf, min: f64, max: f64) -> Result<PathBuf> {
+    let aux_path = {
+        let mut name = output_path.clone().into_os_string();
+        name.push(".aux.xml");
+        PathBuf::from(name)
+    };
+
+    let xml = format!(
+        "<PAMDataset>\n  <PAMRasterBand band=\"1\">\n    <Metadata>\n      <MDI key=\"STATISTICS_MINIMUM\">{min}</MDI>\n      <MDI key=\"STATISTICS_MAXIMUM\">{max}</MDI>\n    </Metadata>\n  </PAMRasterBand>\n</PAMDataset>\n"
+    );
+
+    write_atomically(&aux_path, |tmp_path| Ok(std::fs::write(tmp_path, &xml)?))?;
+
+    info!("wrote {}", aux_path.display());
+
+    Ok(aux_path)
+}
+
+/// Builds the `exr` crate's [`exr::prelude::Encoding`] for `compression`,
+/// keeping [`exr::prelude::Encoding::FAST_LOSSLESS`]'s line order -- only the
+/// compression codec and block layout are user-selectable.
+///
+/// `tile_size`, when given, switches the block layout from scanlines to
+/// `tile_size`-sized tiles (see [`export_dtm_to_exr`]'s `exr_tiled`), so a
+/// downstream reader can seek straight to the tile covering a crop it wants
+/// instead of decoding full scanlines across the image's whole width.
+fn exr_encoding_for(compression: ExrCompression, tile_size: Option<(usize, usize)>) -> exr::prelude::Encoding {
+    use exr::prelude::{Blocks, Compression, Encoding, Vec2};
+
+    let compression = match compression {
+        ExrCompression::None => Compression::Uncompressed,
+        ExrCompression::Rle => Compression::RLE,
+        ExrCompression::Zip => Compression::ZIP16,
+        ExrCompression::Zips => Compression::ZIP1,
+        ExrCompression::Piz => Compression::PIZ,
+        ExrCompression::Pxr24 => Compression::PXR24,
+    };
+
+    let blocks = match tile_size {
+        Some((tile_w, tile_h)) => Blocks::Tiles(Vec2(tile_w, tile_h)),
+        None => Blocks::ScanLines,
+    };
+
+    Encoding { compression, blocks, ..Encoding::FAST_LOSSLESS }
+}
+
+/// Halves `rgb`/`alpha` (a `width`x`height` image, row-major) into the next
+/// mip level down, rounding each dimension down to a minimum of `1`.
+///
+/// Each output sample is the average of up to 2x2 source samples, but only
+/// over the ones with `alpha > 0.0` -- a no-data sample (`alpha == 0.0`)
+/// never contributes its (otherwise meaningless) color into a valid
+/// neighbor's average. An output sample with no valid source samples under
+/// it is itself marked no-data (`alpha = 0.0`, color left at `[0.0; 3]`)
+/// rather than inheriting a sentinel value from its source.
+fn downsample_mip_level(
+    rgb: &[[f32; 3]],
+    alpha: &[f32],
+    width: usize,
+    height: usize,
+) -> (usize, usize, Vec<[f32; 3]>, Vec<f32>) {
+    let next_w = (width / 2).max(1);
+    let next_h = (height / 2).max(1);
+    let mut out_rgb = vec![[0.0f32; 3]; next_w * next_h];
+    let mut out_alpha = vec![0.0f32; next_w * next_h];
+
+    for out_y in 0..next_h {
+        for out_x in 0..next_w {
+            let mut sum = [0.0f32; 3];
+            let mut valid_samples = 0.0f32;
+            for dy in 0..2 {
+                for dx in 0..2 {
+                    let src_x = (out_x * 2 + dx).min(width - 1);
+                    let src_y = (out_y * 2 + dy).min(height - 1);
+                    let src_idx = src_y * width + src_x;
+                    if alpha[src_idx] > 0.0 {
+                        sum[0] += rgb[src_idx][0];
+                        sum[1] += rgb[src_idx][1];
+                        sum[2] += rgb[src_idx][2];
+                        valid_samples += 1.0;
+                    }
+                }
+            }
+
+            let out_idx = out_y * next_w + out_x;
+            if valid_samples > 0.0 {
+                out_rgb[out_idx] = [sum[0] / valid_samples, sum[1] / valid_samples, sum[2] / valid_samples];
+                out_alpha[out_idx] = 1.0;
+            }
+        }
+    }
+
+    (next_w, next_h, out_rgb, out_alpha)
+}
+
+/// Shifts `geo_transform`'s origin to the pixel at `(crop_x, crop_y)` in the
+/// source raster, so georeferencing written out for a [`export_dtm_to_exr`]
+/// `bbox` crop still lines up spatially instead of describing the
+/// uncropped raster's origin.
+fn cropped_geo_transform(geo_transform: &[f64; 6], crop_x: usize, crop_y: usize) -> [f64; 6] {
+    let mut cropped = *geo_transform;
+    cropped[0] += crop_x as f64 * geo_transform[1] + crop_y as f64 * geo_transform[2];
+    cropped[3] += crop_x as f64 * geo_transform[4] + crop_y as f64 * geo_transform[5];
+    cropped
+}
+
+/// Adjusts `geo_transform` (describing a `raster_w`x`raster_h` raster) so it
+/// still describes the same pixel data after `flip_x`/`flip_y` reverse it
+/// along that axis, the same way [`export_dtm_to_exr`]'s `flip_x`/`flip_y`
+/// reverse the pixels actually written out -- without this, a flipped
+/// export's embedded georeferencing (the EXR `GDAL_GeoTransform` attribute,
+/// the GeoTIFF header, the world file) would still describe where the
+/// *unflipped* pixels sat.
+fn flipped_geo_transform(geo_transform: &[f64; 6], raster_w: usize, raster_h: usize, flip_x: bool, flip_y: bool) -> [f64; 6] {
+    let mut flipped = *geo_transform;
+
+    if flip_x {
+        flipped[0] += raster_w as f64 * flipped[1];
+        flipped[3] += raster_w as f64 * flipped[4];
+        flipped[1] = -flipped[1];
+        flipped[4] = -flipped[4];
+    }
+
+    if flip_y {
+        flipped[0] += raster_h as f64 * flipped[2];
+        flipped[3] += raster_h as f64 * flipped[5];
+        flipped[2] = -flipped[2];
+        flipped[5] = -flipped[5];
+    }
+
+    flipped
+}
+
+/// Picks the conventional ESRI world-file extension for `output_path`'s
+/// format (`.tfw` for TIFF, `.pgw` for PNG), falling back to `.exrw` for
+/// anything else since EXR has no standardized world-file suffix.
+fn world_file_extension(output_path: &PathBuf) -> &'static str {
+    match output_path.extension().and_then(|ext| ext.to_str()) {
+        Some("tif") | Some("tiff") => "tfw",
+        Some("png") => "pgw",
+        _ => "exrw",
+    }
+}
+
+/// Writes a 6-line ESRI world file alongside `output_path`, letting GIS
+/// tools that don't read `output_path`'s own header (or that can't, as
+/// with EXR) position the raster correctly anyway.
+fn write_world_file(output_path: &PathBuf, geo_transform: &[f64; 6]) -> Result<PathBuf> {
+    let world_file_path = output_path.with_extension(world_file_extension(output_path));
+
+    let pixel_width = geo_transform[1];
+    let row_rotation = geo_transform[2];
+    let column_rotation = geo_transform[4];
+    let pixel_height = geo_transform[5];
+    let upper_left_x = geo_transform[0] + pixel_width / 2.0 + row_rotation / 2.0;
+    let upper_left_y = geo_transform[3] + column_rotation / 2.0 + pixel_height / 2.0;
+
+    let contents = format!(
+        "{pixel_width}\n{row_rotation}\n{column_rotation}\n{pixel_height}\n{upper_left_x}\n{upper_left_y}\n"
+    );
+
+    write_atomically(&world_file_path, |tmp_path| Ok(std::fs::write(tmp_path, &contents)?))?;
+
+    info!("wrote {}", world_file_path.display());
+
+    Ok(world_file_path)
+}
+
+/// One exported band's min/max and no-data value, as recorded by
+/// [`write_stats_json`].
+#[derive(serde::Serialize)]
+struct BandStatsRecord {
+    band_index: usize,
+    min: Option<f64>,
+    max: Option<f64>,
+    no_data_value: Option<f64>,
+}
+
+/// Provenance record written by [`write_stats_json`] for `export_dtm_to_exr`'s
+/// `stats_json` flag.
+#[derive(serde::Serialize)]
+struct ExportStatsRecord {
+    source_path: String,
+    raster_width: usize,
+    raster_height: usize,
+    spatial_ref_name: String,
+    normalization_mode: &'static str,
+    normalization_range: Option<(f64, f64)>,
+    window_scale_factor: usize,
+    bands: Vec<BandStatsRecord>,
+}
+
+/// Writes a `<output_path>.json` sidecar alongside `output_path` recording
+/// what the export was actually built from -- see `stats_json` on
+/// [`export_dtm_to_exr`] for the field list's rationale.
+#[allow(clippy::too_many_arguments)]
+fn write_stats_json(
+    output_path: &PathBuf,
+    source_path: &PathBuf,
+    dataset: &Dataset,
+    raster_w: usize,
+    raster_h: usize,
+    window_scale_factor: usize,
+    normalization_mode: &'static str,
+    normalization_range: Option<(f64, f64)>,
+    active_bands: &[usize],
+    band_stats: &[Option<(f64, f64)>],
+    band_nodata: &[Option<f64>],
+) -> Result<PathBuf> {
+    let spatial_ref_name = dataset
+        .spatial_ref()
+        .ok()
+        .and_then(|srs| srs.name().ok())
+        .unwrap_or_else(|| "none (no CRS found)".to_string());
+
+    let bands = active_bands
+        .iter()
+        .zip(band_stats)
+        .zip(band_nodata)
+        .map(|((&band_index, &stats), &no_data_value)| BandStatsRecord {
+            band_index,
+            min: stats.map(|(min, _)| min),
+            max: stats.map(|(_, max)| max),
+            no_data_value,
+        })
+        .collect();
+
+    let record = ExportStatsRecord {
+        source_path: source_path.display().to_string(),
+        raster_width: raster_w,
+        raster_height: raster_h,
+        spatial_ref_name,
+        normalization_mode,
+        normalization_range,
+        window_scale_factor,
+        bands,
+    };
+
+    let json_path = {
+        let mut name = output_path.clone().into_os_string();
+        name.push(".json");
+        PathBuf::from(name)
+    };
+
+    let contents = serde_json::to_string_pretty(&record)?;
+    write_atomically(&json_path, |tmp_path| Ok(std::fs::write(tmp_path, &contents)?))?;
+
+    info!("wrote {}", json_path.display());
+
+    Ok(json_path)
+}
+
+/// Writes a single "terrain package" EXR containing elevation (`Z`),
+/// per-pixel surface normals (`normal.X`/`normal.Y`/`normal.Z`) and a valid-
+/// pixel mask (`A`) in one file, so a lookdev artist has everything needed
+/// for shading from a single read.
+///
+/// Normals are derived from a central-difference gradient of the elevation
+/// band; at the raster edge the missing neighbor is replaced by the pixel
+/// itself, giving a halved one-sided difference there. The mask is 0.0
+/// wherever the source sample equals the band's no-data value, 1.0 otherwise.
+pub fn export_dtm_to_exr_package(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    use exr::prelude::*;
+
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+    let nodata = band.no_data_value();
+
+    let buffer = band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+    let elevation = buffer.data();
+
+    let mut normal_x = vec![0.0f32; elevation.len()];
+    let mut normal_y = vec![0.0f32; elevation.len()];
+    let mut normal_z = vec![1.0f32; elevation.len()];
+    let mut mask = vec![1.0f32; elevation.len()];
+
+    for y in 0..raster_h {
+        for x in 0..raster_w {
+            let i = y * raster_w + x;
+
+            if let Some(nodata) = nodata {
+                if (elevation[i] as f64 - nodata).abs() < f64::EPSILON {
+                    mask[i] = 0.0;
+                }
+            }
+
+            let left = if x > 0 { elevation[i - 1] } else { elevation[i] };
+            let right = if x + 1 < raster_w { elevation[i + 1] } else { elevation[i] };
+            let up = if y > 0 { elevation[i - raster_w] } else { elevation[i] };
+            let down = if y + 1 < raster_h { elevation[i + raster_w] } else { elevation[i] };
+
+            let dz_dx = (right - left) * 0.5;
+            let dz_dy = (down - up) * 0.5;
+            let length = (dz_dx * dz_dx + dz_dy * dz_dy + 1.0).sqrt();
+
+            normal_x[i] = -dz_dx / length;
+            normal_y[i] = -dz_dy / length;
+            normal_z[i] = 1.0 / length;
+        }
+    }
+
+    std::fs::create_dir_all(export_dir)?;
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("exr");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    let channels = AnyChannels::sort(smallvec::smallvec![
+        AnyChannel::new("Z", FlatSamples::F32(elevation.to_vec())),
+        AnyChannel::new("normal.X", FlatSamples::F32(normal_x)),
+        AnyChannel::new("normal.Y", FlatSamples::F32(normal_y)),
+        AnyChannel::new("normal.Z", FlatSamples::F32(normal_z)),
+        AnyChannel::new("A", FlatSamples::F32(mask)),
+    ]);
+
+    let layer = Layer::new(
+        (raster_w, raster_h),
+        LayerAttributes::named("terrain-package"),
+        Encoding::FAST_LOSSLESS,
+        channels,
+    );
+
+    write_atomically(&output_path, |tmp_path| {
+        Ok(Image::from_layer(layer).write().to_file(tmp_path)?)
+    })?;
+
+    info!("wrote terrain package {}", output_path.display());
+
+    Ok(output_path)
+}
+
+/// Summary of a dataset's georeferencing and elevation range, for display
+/// before committing to a full export.
+#[derive(Debug, Clone)]
+pub struct DatasetInfo {
+    pub crs_name: String,
+    pub raster_width: usize,
+    pub raster_height: usize,
+    pub band_count: usize,
+    pub elevation_min: f64,
+    pub elevation_max: f64,
+    /// `(key, value)` pairs found among [`ELEVATION_OFFSET_METADATA_KEYS`],
+    /// surfaced so users know `--use-metadata-offset` has something to act on.
+    pub detected_elevation_offset: Option<(String, f64)>,
+}
+
+/// Per-band metadata reported by [`inspect_dtm`].
+#[derive(Debug, Clone)]
+pub struct BandInfo {
+    pub min: f64,
+    pub max: f64,
+    pub no_data_value: Option<f64>,
+    pub data_type: String,
+}
+
+/// Full metadata-only report returned by [`inspect_dtm`].
+#[derive(Debug, Clone)]
+pub struct DtmInfo {
+    pub driver_name: String,
+    pub crs_name: String,
+    pub raster_width: usize,
+    pub raster_height: usize,
+    pub bands: Vec<BandInfo>,
+}
+
+/// Reads `path`'s metadata -- driver, CRS, dimensions, and each band's
+/// min/max, no-data value and pixel type -- without decoding the full
+/// raster into memory, so callers can inspect a DTM before committing to a
+/// long export.
+pub fn inspect_dtm(path: &PathBuf) -> Result<DtmInfo> {
+    let dataset = Dataset::open(path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+
+    let driver_name = dataset.driver().short_name();
+    let crs_name = dataset
+        .spatial_ref()
+        .map(|srs| srs.name().unwrap_or_else(|_| "unknown".to_string()))
+        .unwrap_or_else(|_| "none (no CRS found)".to_string());
+
+    let mut bands = Vec::with_capacity(dataset.raster_count());
+    for band_index in 1..=dataset.raster_count() {
+        let band = dataset.rasterband(band_index)?;
+        check_supported_pixel_type(&band)?;
+        let stats = band.compute_raster_min_max(true)?;
+
+        bands.push(BandInfo {
+            min: stats.min,
+            max: stats.max,
+            no_data_value: band.no_data_value(),
+            data_type: format!("{:?}", band.band_type()),
+        });
+    }
+
+    Ok(DtmInfo {
+        driver_name,
+        crs_name,
+        raster_width: raster_w,
+        raster_height: raster_h,
+        bands,
+    })
+}
+
+/// A `bins`-bucket elevation histogram, as returned by [`elevation_histogram`].
+/// `counts[i]` is the number of valid samples in
+/// `[min + i * bin_width, min + (i + 1) * bin_width)`, with the last bucket
+/// closed on both ends so the band's own max value lands in it rather than
+/// in an (absent) extra bucket past the end.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub bin_width: f64,
+    pub counts: Vec<u64>,
+}
+
+/// Computes a `bins`-bucket elevation histogram over `band_index` of
+/// `path`, excluding no-data samples.
+///
+/// Reads the band in row tiles sized by [`suggest_window_scale_factor`]
+/// against [`DEFAULT_MEMORY_BUDGET_BYTES`], the same way `--auto-window`
+/// picks a tile size for export, rather than [`band_percentile_range`]'s
+/// whole-band-at-once read -- a histogram is the kind of one-off inspection
+/// a caller might run against a raster too large to comfortably read in
+/// full.
+pub fn elevation_histogram(path: &PathBuf, band_index: usize, bins: usize) -> Result<Histogram> {
+    if bins == 0 {
+        return Err(eyre!("histogram bin count must be at least 1"));
+    }
+
+    let dataset = Dataset::open(path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(band_index)?;
+    check_supported_pixel_type(&band)?;
+
+    let stats = band.compute_raster_min_max(true)?;
+    let (min, max) = (stats.min, stats.max);
+    let nodata = band.no_data_value();
+    let bin_width = (max - min) / bins as f64;
+
+    let window_scale_factor = suggest_window_scale_factor(raster_w, raster_h, 1, DEFAULT_MEMORY_BUDGET_BYTES);
+    let (region_w, region_h) = tile_region_size(raster_w, raster_h, window_scale_factor);
+
+    let mut counts = vec![0u64; bins];
+    let mut y = 0;
+    while y < raster_h {
+        let read_h = region_h.min(raster_h - y);
+        let mut x = 0;
+        while x < raster_w {
+            let read_w = region_w.min(raster_w - x);
+            let tile = band.read_as::<f32>((x as isize, y as isize), (read_w, read_h), (read_w, read_h), None)?;
+
+            for &value in tile.data() {
+                if let Some(nd) = nodata {
+                    if value as f64 == nd {
+                        continue;
+                    }
+                }
+
+                let bin = if bin_width > 0.0 {
+                    (((value as f64 - min) / bin_width) as usize).min(bins - 1)
+                } else {
+                    // A perfectly flat band: every valid sample lands in the
+                    // single bucket a zero-span range collapses to.
+                    0
+                };
+                counts[bin] += 1;
+            }
+
+            x += read_w;
+        }
+        y += read_h;
+    }
+
+    Ok(Histogram { min, max, bin_width, counts })
+}
+
+/// Reads just enough of `in_image_path` to report its CRS, dimensions, band
+/// count and elevation range, without decoding the full raster.
+///
+/// Factored out of the CLI's `export_dtm_to_exr` logging so the GUI (and any
+/// other caller) can show the same information after a file is picked. Built
+/// on top of [`inspect_dtm`], reusing band 1's stats instead of recomputing
+/// them.
+pub fn describe_dataset(in_image_path: &PathBuf) -> Result<DatasetInfo> {
+    let info = inspect_dtm(in_image_path)?;
+    let band_one = info
+        .bands
+        .first()
+        .ok_or_else(|| eyre!("dataset has no bands"))?;
+
+    let dataset = Dataset::open(in_image_path)?;
+    let detected_elevation_offset = detect_elevation_offset(&dataset)?;
+
+    Ok(DatasetInfo {
+        crs_name: info.crs_name,
+        raster_width: info.raster_width,
+        raster_height: info.raster_height,
+        band_count: info.bands.len(),
+        elevation_min: band_one.min,
+        elevation_max: band_one.max,
+        detected_elevation_offset,
+    })
+}
+
+/// One output tile's footprint, as computed by [`build_tile_manifest`].
+#[derive(Debug, Clone)]
+pub struct TileManifestEntry {
+    pub file_name: String,
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+/// Computes the geographic extent of every tile in a `tile_cols` x
+/// `tile_rows` grid covering `in_image_path`, using its geotransform.
+///
+/// Used by [`plan_size_split_tiles`] to report a planned split's layout;
+/// nothing in this crate writes the entries out as a JSON manifest.
+pub fn build_tile_manifest(
+    in_image_path: &PathBuf,
+    tile_cols: usize,
+    tile_rows: usize,
+) -> Result<Vec<TileManifestEntry>> {
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let geo_transform = dataset.geo_transform()?;
+
+    let tile_w = raster_w / tile_cols;
+    let tile_h = raster_h / tile_rows;
+
+    let pixel_to_world = |px: f64, py: f64| -> (f64, f64) {
+        let x = geo_transform[0] + px * geo_transform[1] + py * geo_transform[2];
+        let y = geo_transform[3] + px * geo_transform[4] + py * geo_transform[5];
+        (x, y)
+    };
+
+    let mut entries = Vec::with_capacity(tile_cols * tile_rows);
+
+    for row in 0..tile_rows {
+        for col in 0..tile_cols {
+            let px0 = (col * tile_w) as f64;
+            let py0 = (row * tile_h) as f64;
+            let px1 = px0 + tile_w as f64;
+            let py1 = py0 + tile_h as f64;
+
+            let (x0, y0) = pixel_to_world(px0, py0);
+            let (x1, y1) = pixel_to_world(px1, py1);
+
+            entries.push(TileManifestEntry {
+                file_name: format!("tile_{row}_{col}.exr"),
+                min_x: x0.min(x1),
+                min_y: y0.min(y1),
+                max_x: x0.max(x1),
+                max_y: y0.max(y1),
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Estimated on-disk size, in bytes, of an uncompressed `width`x`height`
+/// tile with `bytes_per_pixel` (e.g. 4 for one f32 band).
+fn estimate_tile_bytes(width: usize, height: usize, bytes_per_pixel: usize) -> u64 {
+    width as u64 * height as u64 * bytes_per_pixel as u64
+}
+
+/// Picks the smallest `tile_cols` x `tile_rows` grid that keeps every tile's
+/// estimated output under `max_bytes`, growing whichever axis currently
+/// produces the larger tile dimension so tiles stay roughly square.
+fn tile_grid_for_size_limit(
+    raster_w: usize,
+    raster_h: usize,
+    bytes_per_pixel: usize,
+    max_bytes: u64,
+) -> (usize, usize) {
+    let mut tile_cols = 1;
+    let mut tile_rows = 1;
+
+    while estimate_tile_bytes(raster_w / tile_cols, raster_h / tile_rows, bytes_per_pixel) > max_bytes {
+        if raster_w / tile_cols >= raster_h / tile_rows {
+            tile_cols += 1;
+        } else {
+            tile_rows += 1;
+        }
+    }
+
+    (tile_cols, tile_rows)
+}
+
+/// Works out the `--split-output-by-size` tile layout for `in_image_path`:
+/// the smallest grid of spatially-tiled outputs that keeps every tile under
+/// `max_bytes`, reusing [`build_tile_manifest`] to report each tile's
+/// geographic footprint.
+///
+/// Diagnostics only, deliberately: this plans and reports the layout a
+/// split would use, but does not write any tile files itself -- there's no
+/// multi-file tiled writer in this crate to actually perform the split.
+/// `--split-output-by-size` stays a size-limit *warning* with a suggested
+/// layout, not a tiled export mode; a caller that wants the tiles on disk
+/// has to export each [`TileManifestEntry`]'s footprint itself (e.g. via
+/// [`export_dtm_to_exr`]'s `bbox` parameter) until a real tiled writer
+/// exists here.
+pub fn plan_size_split_tiles(
+    in_image_path: &PathBuf,
+    max_bytes: u64,
+) -> Result<(usize, usize, Vec<TileManifestEntry>)> {
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let bytes_per_pixel = 4 * dataset.raster_count();
+
+    let (tile_cols, tile_rows) =
+        tile_grid_for_size_limit(raster_w, raster_h, bytes_per_pixel, max_bytes);
+
+    let entries = build_tile_manifest(in_image_path, tile_cols, tile_rows)?;
+
+    Ok((tile_cols, tile_rows, entries))
+}
+
+/// A dataset's pixel data read into memory once, keyed by band.
+///
+/// The write side of this crate (`export_dtm_to_exr`, `export_dtm_to_png`,
+/// `export_multiband_to_tiff`, ...) each open and read `in_image_path`
+/// themselves, which is fine when only one output is being produced. When a
+/// caller wants several outputs from the same input -- e.g. an EXR and a
+/// PNG in one invocation -- reading through [`RasterCache::load`] once and
+/// handing the resulting bands to each writer avoids paying for the GDAL
+/// read per output.
+///
+/// There's no multi-output CLI entry point wired up to this yet (`Export`
+/// still produces exactly one output per invocation), so nothing in this
+/// crate constructs a `RasterCache` today; it's here so that feature can
+/// build on it instead of re-reading per writer once it exists.
+pub struct RasterCache {
+    pub bands: Vec<Vec<f32>>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl RasterCache {
+    /// Reads every band of `in_image_path` fully into memory.
+    pub fn load(in_image_path: &PathBuf) -> Result<RasterCache> {
+        let dataset = Dataset::open(in_image_path)?;
+        let (width, height) = dataset.raster_size();
+        let band_count = dataset.raster_count();
+
+        let mut bands = Vec::with_capacity(band_count);
+        for band_index in 1..=band_count {
+            let band = dataset.rasterband(band_index)?;
+            check_supported_pixel_type(&band)?;
+            let buffer = band.read_as::<f32>((0, 0), (width, height), (width, height), None)?;
+            bands.push(buffer.data().to_vec());
+        }
+
+        Ok(RasterCache { bands, width, height })
+    }
+}
+
+/// Bilinearly samples `grid` (row-major, `width * height` elements) at the
+/// fractional pixel coordinate `(x, y)`, excluding no-data neighbors from the
+/// interpolation weights instead of letting them contaminate the result.
+///
+/// Falls back to `nodata` only when all four contributing corners are
+/// no-data. Intended for mesh/vertex resampling, where a plain bilinear
+/// sample across a no-data hole would otherwise smear garbage elevations
+/// into valid neighboring vertices.
+pub fn sample_bilinear_nodata_aware(
+    grid: &[f32],
+    width: usize,
+    height: usize,
+    x: f64,
+    y: f64,
+    nodata: f32,
+) -> f32 {
+    let x0 = x.floor().clamp(0.0, (width - 1) as f64) as usize;
+    let y0 = y.floor().clamp(0.0, (height - 1) as f64) as usize;
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+
+    let fx = (x - x0 as f64).clamp(0.0, 1.0) as f32;
+    let fy = (y - y0 as f64).clamp(0.0, 1.0) as f32;
+
+    let corners = [
+        (grid[y0 * width + x0], (1.0 - fx) * (1.0 - fy)),
+        (grid[y0 * width + x1], fx * (1.0 - fy)),
+        (grid[y1 * width + x0], (1.0 - fx) * fy),
+        (grid[y1 * width + x1], fx * fy),
+    ];
+
+    let mut weighted_sum = 0.0f32;
+    let mut weight_total = 0.0f32;
+
+    for (value, weight) in corners {
+        if value != nodata {
+            weighted_sum += value * weight;
+            weight_total += weight;
+        }
+    }
+
+    if weight_total == 0.0 {
+        nodata
+    } else {
+        weighted_sum / weight_total
+    }
+}
+
+/// Writes a single-band raster to a headerless file of native-endian `f32`
+/// values, row-major, via a memory-mapped file.
+///
+/// This is meant for rasters too large to comfortably hold in RAM even when
+/// streaming: the OS pages the output in and out as needed instead of the
+/// process buffering it. It only works for formats with a fixed, computable
+/// byte layout (no header, no compression), which is why the output here is
+/// raw floats rather than EXR/TIFF/PNG.
+///
+/// `erode_iterations` runs [`erosion::apply_thermal_erosion`] over the
+/// output before writing, which needs the whole grid in memory at once --
+/// the opposite of this function's own memory-mapped, streamed-tile write.
+/// Since [`erosion::apply_thermal_erosion`] is only meant for small preview
+/// grids anyway, erosion is skipped (with a warning) once the raster
+/// exceeds [`DEFAULT_MEMORY_BUDGET_BYTES`], rather than silently
+/// materializing an arbitrarily large copy in RAM.
+pub fn export_dtm_to_raw_mmap(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    window_scale_factor: usize,
+    overwrite: bool,
+    erode_iterations: usize,
+    erode_talus_angle: f32,
+) -> Result<PathBuf> {
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("raw32");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    let mut tmp_name = output_path.clone().into_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    let file_len = (raster_w * raster_h * std::mem::size_of::<f32>()) as u64;
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&tmp_path)?;
+    file.set_len(file_len)?;
+
+    let write_result: Result<()> = (|| {
+        let mut mmap = unsafe { memmap2::MmapMut::map_mut(&file)? };
+
+        let (region_size_w, region_size_h) = tile_region_size(raster_w, raster_h, window_scale_factor);
+
+        read_band_tiled(&band, raster_w, raster_h, region_size_w, region_size_h, |x_offset, y_offset, tile_w, _tile_h, data| {
+            for (i, &value) in data.iter().enumerate() {
+                let px = x_offset + i % tile_w;
+                let py = y_offset + i / tile_w;
+                let byte_offset = (py * raster_w + px) * std::mem::size_of::<f32>();
+
+                mmap[byte_offset..byte_offset + std::mem::size_of::<f32>()].copy_from_slice(&value.to_ne_bytes());
+            }
+            Ok(())
+        })?;
+
+        if erode_iterations > 0 {
+            let grid_bytes = raster_w as u64 * raster_h as u64 * std::mem::size_of::<f32>() as u64;
+            if grid_bytes > DEFAULT_MEMORY_BUDGET_BYTES {
+                log::warn!(
+                    "--erode would materialize the full {raster_w}x{raster_h} raster in RAM ({grid_bytes} bytes), \
+                     defeating --raw-mmap's point; skipping erosion. erosion::apply_thermal_erosion is meant for \
+                     small preview grids -- erode a downsampled proxy separately instead"
+                );
+            } else {
+                let mut grid: Vec<f32> = (0..raster_w * raster_h)
+                    .map(|i| {
+                        let byte_offset = i * std::mem::size_of::<f32>();
+                        f32::from_ne_bytes(mmap[byte_offset..byte_offset + std::mem::size_of::<f32>()].try_into().unwrap())
+                    })
+                    .collect();
+
+                erosion::apply_thermal_erosion(&mut grid, raster_w, raster_h, erode_iterations, erode_talus_angle);
+
+                for (i, &value) in grid.iter().enumerate() {
+                    let byte_offset = i * std::mem::size_of::<f32>();
+                    mmap[byte_offset..byte_offset + std::mem::size_of::<f32>()].copy_from_slice(&value.to_ne_bytes());
+                }
+            }
+        }
+
+        mmap.flush()?;
+
+        Ok(())
+    })();
+
+    if let Err(err) = write_result {
+        std::fs::remove_file(&tmp_path).ok();
+        return Err(err);
+    }
+
+    std::fs::rename(&tmp_path, &output_path)?;
+
+    info!("wrote {}", output_path.display());
+
+    Ok(output_path)
+}
+
+/// On-disk encoding for [`export_dtm_to_ply`]'s output.
+///
+/// ASCII is human-readable and easy to diff but produces multi-gigabyte
+/// files for a dense terrain mesh; [`PlyFormat::BinaryLittleEndian`] packs
+/// the same vertices/faces as raw bytes instead, at a fraction of the size
+/// and with no floating-point text round-trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+fn write_ply_header(
+    writer: &mut impl Write,
+    num_points: usize,
+    num_faces: usize,
+    format: PlyFormat,
+    with_colors: bool,
+) -> std::io::Result<()> {
+    let format_line = match format {
+        PlyFormat::Ascii => "format ascii 1.0",
+        PlyFormat::BinaryLittleEndian => "format binary_little_endian 1.0",
+    };
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "{format_line}")?;
+    writeln!(writer, "comment exported by gdal-dtm-exporter")?;
+    writeln!(writer, "element vertex {}", num_points)?;
+    writeln!(writer, "property float x")?;
+    writeln!(writer, "property float y")?;
+    writeln!(writer, "property float z")?;
+    if with_colors {
+        writeln!(writer, "property uchar red")?;
+        writeln!(writer, "property uchar green")?;
+        writeln!(writer, "property uchar blue")?;
+    }
+    writeln!(writer, "element face {}", num_faces)?;
+    writeln!(writer, "property list uchar int vertex_indices")?;
+    writeln!(writer, "end_header")?;
+    Ok(())
+}
+
+fn write_ply_chunk(writer: &mut impl Write, points: &[mesh::Vertex], colors: Option<&[[u8; 3]]>) -> std::io::Result<()> {
+    for (i, point) in points.iter().enumerate() {
+        match colors {
+            Some(colors) => {
+                let [r, g, b] = colors[i];
+                writeln!(writer, "{} {} {} {r} {g} {b}", point.x, point.y, point.z)?;
+            }
+            None => writeln!(writer, "{} {} {}", point.x, point.y, point.z)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_ply_chunk_binary(writer: &mut impl Write, points: &[mesh::Vertex], colors: Option<&[[u8; 3]]>) -> std::io::Result<()> {
+    for (i, point) in points.iter().enumerate() {
+        writer.write_all(&point.x.to_le_bytes())?;
+        writer.write_all(&point.y.to_le_bytes())?;
+        writer.write_all(&point.z.to_le_bytes())?;
+        if let Some(colors) = colors {
+            writer.write_all(&colors[i])?;
+        }
+    }
+    Ok(())
+}
+
+fn write_ply_faces(writer: &mut impl Write, faces: &[[usize; 3]]) -> std::io::Result<()> {
+    for face in faces {
+        writeln!(writer, "3 {} {} {}", face[0], face[1], face[2])?;
+    }
+    Ok(())
+}
+
+fn write_ply_faces_binary(writer: &mut impl Write, faces: &[[usize; 3]]) -> std::io::Result<()> {
+    for face in faces {
+        writer.write_all(&[3u8])?;
+        for &index in face {
+            writer.write_all(&(index as i32).to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Samples `in_image_path`'s first band into a grid of [`mesh::Vertex`]es
+/// (downsampled by `window_scale_factor`, same as [`export_dtm_to_exr`]'s own
+/// knob of the same name) plus a parallel no-data mask, shared by
+/// [`export_dtm_to_ply`] and [`export_dtm_to_obj`] so both lay samples out
+/// identically: `x`/`z` are the source pixel column/row (fractional once
+/// downsampled) and `y` is the elevation, scaled by `y_scale`. The returned
+/// `usize`s are the grid's actual width/height, which only equal the raster's
+/// own when `window_scale_factor` is 1.
+///
+/// Downsampling reads the full-resolution band once into memory, then
+/// bilinearly resamples it down with [`sample_bilinear_nodata_aware`] rather
+/// than GDAL's own resampler, so a no-data hole's sentinel value can't smear
+/// into a neighboring vertex's interpolated elevation the way a plain
+/// bilinear read would.
+///
+/// `flip_y`, when set, inverts the `z` coordinate (`height - 1 - py`
+/// instead of `py`) so the mesh isn't upside-down in a tool that assumes a
+/// bottom-left rather than GDAL's top-left row origin -- the same flip
+/// [`export_dtm_to_exr`]'s `flip_y` applies to its own output image. The
+/// vertex array's layout is untouched (still row-major by source row/column),
+/// so [`triangulate_grid`] triangulates a flipped grid exactly like an
+/// unflipped one.
+fn sample_mesh_grid(
+    in_image_path: &PathBuf,
+    y_scale: f32,
+    window_scale_factor: usize,
+    flip_y: bool,
+) -> Result<(Vec<mesh::Vertex>, Vec<bool>, usize, usize)> {
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+    let nodata = band.no_data_value();
+    let nodata_sentinel = nodata.unwrap_or(f64::NAN) as f32;
+
+    let (region_size_w, region_size_h) = tile_region_size(raster_w, raster_h, window_scale_factor);
+
+    let mut heights = vec![0.0f32; raster_w * raster_h];
+    let mut source_is_nodata = vec![false; raster_w * raster_h];
+
+    read_band_tiled(&band, raster_w, raster_h, region_size_w, region_size_h, |x_offset, y_offset, tile_w, _tile_h, data| {
+        for (i, &elevation) in data.iter().enumerate() {
+            let px = x_offset + i % tile_w;
+            let py = y_offset + i / tile_w;
+            let index = py * raster_w + px;
+
+            heights[index] = elevation;
+            source_is_nodata[index] = nodata
+                .map(|nodata| (elevation as f64 - nodata).abs() < f64::EPSILON)
+                .unwrap_or(false);
+        }
+        Ok(())
+    })?;
+
+    let out_w = (raster_w / window_scale_factor).max(1);
+    let out_h = (raster_h / window_scale_factor).max(1);
+
+    let mut points = Vec::with_capacity(out_w * out_h);
+    let mut is_nodata = Vec::with_capacity(out_w * out_h);
+
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let (elevation, point_is_nodata) = if out_w == raster_w && out_h == raster_h {
+                let index = oy * raster_w + ox;
+                (heights[index], source_is_nodata[index])
+            } else {
+                let sx = if out_w > 1 { ox as f64 * (raster_w - 1) as f64 / (out_w - 1) as f64 } else { 0.0 };
+                let sy = if out_h > 1 { oy as f64 * (raster_h - 1) as f64 / (out_h - 1) as f64 } else { 0.0 };
+                let sampled = sample_bilinear_nodata_aware(&heights, raster_w, raster_h, sx, sy, nodata_sentinel);
+                let sampled_is_nodata = nodata.is_some() && (sampled - nodata_sentinel).abs() < f32::EPSILON;
+                (sampled, sampled_is_nodata)
+            };
+
+            let sx = if out_w > 1 { ox as f32 * (raster_w - 1) as f32 / (out_w - 1) as f32 } else { 0.0 };
+            let sy = if out_h > 1 { oy as f32 * (raster_h - 1) as f32 / (out_h - 1) as f32 } else { 0.0 };
+
+            points.push(mesh::Vertex {
+                x: sx,
+                y: elevation * y_scale,
+                z: if flip_y { (raster_h - 1) as f32 - sy } else { sy },
+            });
+            is_nodata.push(point_is_nodata);
+        }
+    }
+
+    Ok((points, is_nodata, out_w, out_h))
+}
+
+/// Picks the smallest stride [`decimate_grid`] can apply to a
+/// `raster_w`x`raster_h` grid to bring its face count at or under
+/// `max_faces`, by inverting `decimate_grid`'s own face-count formula
+/// (`2 * (w' - 1) * (h' - 1)`, with `w'`/`h'` shrinking by roughly
+/// `1 / stride` in each axis) and rounding the resulting stride up -- the
+/// same "round up so the budget is a ceiling, not a target" approach
+/// [`suggest_window_scale_factor`] uses for its own memory budget. Returns
+/// `1` (no decimation) if the full grid is already within budget, or if
+/// `max_faces` is `0` (which can't be met by decimation alone, since even
+/// a single triangle is one face).
+pub fn decimation_stride_for_max_faces(raster_w: usize, raster_h: usize, max_faces: usize) -> usize {
+    let full_faces = 2 * raster_w.saturating_sub(1) * raster_h.saturating_sub(1);
+    if max_faces == 0 || full_faces <= max_faces {
+        return 1;
+    }
+
+    let ratio = full_faces as f64 / max_faces as f64;
+    (ratio.sqrt().ceil() as usize).max(1)
+}
+
+/// Subsamples a `raster_w`x`raster_h` mesh grid (as produced by
+/// [`sample_mesh_grid`]) down to every `stride`th row and column, keeping
+/// the last row/column even when it falls off the stride so the far edge
+/// of the raster isn't silently cropped out of the decimated mesh. A
+/// `stride` of `1` (or less) returns the grid unchanged.
+///
+/// This is the "naive stride-based decimation" [`export_dtm_to_ply`] and
+/// [`export_dtm_to_obj`] apply before triangulating: since the kept
+/// samples are still laid out on a regular grid, [`triangulate_grid`] can
+/// triangulate the decimated grid exactly the way it triangulates the
+/// full one, so faces stay consistent rather than needing a separate
+/// point-cloud triangulation step.
+///
+/// `method` controls how elevations are prepared before the stride picks
+/// its samples: [`filters::DownsampleMethod::Average`] picks raw elevations
+/// (the original behavior), while `Gaussian`/`Lanczos` low-pass the full-
+/// resolution elevation grid first via [`filters::low_pass_for_decimation`],
+/// so the stride doesn't alias high-frequency terrain into the decimated
+/// proxy -- the same antialiasing tradeoff `--downsample-method` offers for
+/// raster proxies, applied here to mesh decimation.
+fn decimate_grid(
+    points: &[mesh::Vertex],
+    is_nodata: &[bool],
+    raster_w: usize,
+    raster_h: usize,
+    stride: usize,
+    method: filters::DownsampleMethod,
+) -> (Vec<mesh::Vertex>, Vec<bool>, usize, usize) {
+    if stride <= 1 {
+        return (points.to_vec(), is_nodata.to_vec(), raster_w, raster_h);
+    }
+
+    let elevations: Vec<f32> = points.iter().map(|point| point.y).collect();
+    let filtered_elevations = filters::low_pass_for_decimation(&elevations, raster_w, raster_h, stride, method);
+
+    let xs = strided_indices(raster_w, stride);
+    let ys = strided_indices(raster_h, stride);
+
+    let mut new_points = Vec::with_capacity(xs.len() * ys.len());
+    let mut new_is_nodata = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let index = y * raster_w + x;
+            let mut point = points[index];
+            point.y = filtered_elevations[index];
+            new_points.push(point);
+            new_is_nodata.push(is_nodata[index]);
+        }
+    }
+
+    (new_points, new_is_nodata, xs.len(), ys.len())
+}
+
+/// `0, stride, 2 * stride, ...` up to (and always including) `len - 1`.
+fn strided_indices(len: usize, stride: usize) -> Vec<usize> {
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let mut indices: Vec<usize> = (0..len).step_by(stride).collect();
+    if *indices.last().unwrap() != len - 1 {
+        indices.push(len - 1);
+    }
+    indices
+}
+
+/// Builds the face-exclusion mask [`triangulate_grid`] takes, combining
+/// `is_nodata` with (when `target_vertices` is given) [`mesh::adaptive_decimation_mask`]'s
+/// keep-mask: a cell is excluded from faces if it's no-data *or* the
+/// adaptive mask thinned it out for being in a low-curvature (flat) area.
+///
+/// This doesn't drop the thinned cells from the vertex list itself -- same
+/// as no-data cells, they're still written out, just never referenced by a
+/// face -- so flat terrain ends up with visibly sparser faces around its
+/// thinned vertices rather than a re-triangulated coarser mesh there,
+/// exactly the way a no-data hole looks today.
+fn mesh_face_exclusion_mask(
+    points: &[mesh::Vertex],
+    is_nodata: &[bool],
+    raster_w: usize,
+    raster_h: usize,
+    target_vertices: Option<usize>,
+) -> Vec<bool> {
+    match target_vertices {
+        Some(target_vertices) => {
+            let heights: Vec<f32> = points.iter().map(|point| point.y).collect();
+            let keep = mesh::adaptive_decimation_mask(&heights, raster_w, raster_h, target_vertices);
+            is_nodata.iter().zip(keep.iter()).map(|(&nodata, &kept)| nodata || !kept).collect()
+        }
+        None => is_nodata.to_vec(),
+    }
+}
+
+/// Triangulates a `raster_w`x`raster_h` grid into two triangles per 2x2
+/// block of neighboring cells (the usual `(tl, bl, tr)` / `(tr, bl, br)`
+/// split), dropping any face with a no-data vertex so holes in the source
+/// raster become holes in the mesh instead of spikes down to zero.
+fn triangulate_grid(is_nodata: &[bool], raster_w: usize, raster_h: usize) -> Vec<[usize; 3]> {
+    let mut faces = Vec::new();
+    for y in 0..raster_h.saturating_sub(1) {
+        for x in 0..raster_w.saturating_sub(1) {
+            let top_left = y * raster_w + x;
+            let top_right = top_left + 1;
+            let bottom_left = top_left + raster_w;
+            let bottom_right = bottom_left + 1;
+
+            if is_nodata[top_left] || is_nodata[top_right] || is_nodata[bottom_left] || is_nodata[bottom_right] {
+                continue;
+            }
+
+            faces.push([top_left, bottom_left, top_right]);
+            faces.push([top_right, bottom_left, bottom_right]);
+        }
+    }
+
+    faces
+}
+
+/// Reads `in_image_path`'s first band and writes it as a triangulated PLY
+/// mesh: one vertex per sampled cell, triangulated by [`triangulate_grid`].
+///
+/// This is the PLY counterpart of [`export_dtm_to_raw_mmap`]'s tiled read
+/// loop, ported from the legacy `gdal-dtm-exporter` prototype so it's
+/// callable as a library function instead of a standalone binary.
+///
+/// `ply_colors`, when given, adds `red`/`green`/`blue` `uchar` vertex
+/// properties, colored via [`colormap::apply`] driven by each vertex's
+/// elevation normalized against the band's own min/max -- the same
+/// normalization [`export_dtm_to_exr`] uses for its own colormap support,
+/// so a mesh and an EXR colored with the same map line up visually.
+/// `None` writes the plain `x`/`y`/`z`-only PLY this function always wrote
+/// before.
+///
+/// `decimate`, when greater than `1`, subsamples the grid with
+/// [`decimate_grid`] before triangulating, trading mesh density for a
+/// lighter output; pass `1` for the full-resolution mesh this function
+/// always wrote before. Use [`decimation_stride_for_max_faces`] to derive
+/// a stride from a target face count instead of picking a stride directly.
+/// `downsample_method` controls how `decimate_grid` prepares elevations
+/// before striding -- see [`decimate_grid`].
+///
+/// `weld_tolerance`, when given, runs [`mesh::weld_vertices`] over the
+/// triangulated mesh before writing it out, merging vertices that land
+/// within that world-space tolerance of each other and re-indexing faces to
+/// match -- meant for mosaic-derived/tiled mesh input, where shared edge
+/// vertices would otherwise be duplicated and leave visible seams.
+///
+/// `target_vertices`, when given, thins faces over low-curvature (flat)
+/// terrain to stay near that vertex budget instead of uniformly striding --
+/// see [`mesh_face_exclusion_mask`]. Mutually exclusive with `decimate`
+/// being anything other than `1`.
+///
+/// `flip_y` inverts the row coordinate -- see [`sample_mesh_grid`].
+#[allow(clippy::too_many_arguments)]
+pub fn export_dtm_to_ply(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    y_scale: f32,
+    window_scale_factor: usize,
+    overwrite: bool,
+    format: PlyFormat,
+    ply_colors: Option<colormap::Colormap>,
+    decimate: usize,
+    downsample_method: filters::DownsampleMethod,
+    weld_tolerance: Option<f32>,
+    target_vertices: Option<usize>,
+    flip_y: bool,
+) -> Result<PathBuf> {
+    let (points, is_nodata, raster_w, raster_h) = sample_mesh_grid(in_image_path, y_scale, window_scale_factor, flip_y)?;
+    let (points, is_nodata, raster_w, raster_h) =
+        decimate_grid(&points, &is_nodata, raster_w, raster_h, decimate, downsample_method);
+    let exclude = mesh_face_exclusion_mask(&points, &is_nodata, raster_w, raster_h, target_vertices);
+    let faces = triangulate_grid(&exclude, raster_w, raster_h);
+    let (points, faces) = match weld_tolerance {
+        Some(tolerance) => mesh::weld_vertices(&points, &faces, tolerance),
+        None => (points, faces),
+    };
+
+    let colors = if let Some(map) = ply_colors {
+        let dataset = Dataset::open(in_image_path)?;
+        let band = dataset.rasterband(1)?;
+        let (native_w, native_h) = dataset.raster_size();
+        let range = band_min_max_excluding_nodata(&band, 0, 0, native_w, native_h)?;
+
+        Some(
+            points
+                .iter()
+                .map(|point| {
+                    let elevation = if y_scale != 0.0 { point.y / y_scale } else { point.y } as f64;
+                    let normalized = checked_map_range(elevation, range, (0.0, 1.0)).unwrap_or(0.0) as f32;
+                    let rgb = colormap::apply(map, normalized);
+                    [
+                        (rgb.0[0].clamp(0.0, 1.0) * 255.0).round() as u8,
+                        (rgb.0[1].clamp(0.0, 1.0) * 255.0).round() as u8,
+                        (rgb.0[2].clamp(0.0, 1.0) * 255.0).round() as u8,
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        None
+    };
+    let colors = colors.as_deref();
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("ply");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    write_atomically(&output_path, |tmp_path| {
+        let file = std::fs::File::create(tmp_path)?;
+        let mut writer = io::BufWriter::new(file);
+        write_ply_header(&mut writer, points.len(), faces.len(), format, colors.is_some())?;
+        match format {
+            PlyFormat::Ascii => {
+                write_ply_chunk(&mut writer, &points, colors)?;
+                write_ply_faces(&mut writer, &faces)?;
+            }
+            PlyFormat::BinaryLittleEndian => {
+                write_ply_chunk_binary(&mut writer, &points, colors)?;
+                write_ply_faces_binary(&mut writer, &faces)?;
+            }
+        }
+        Ok(())
+    })?;
+
+    info!("wrote {} ({} vertices, {} faces)", output_path.display(), points.len(), faces.len());
+
+    Ok(output_path)
+}
+
+/// Writes `points`/`faces` (see [`sample_mesh_grid`]/[`triangulate_grid`])
+/// as a Wavefront OBJ mesh: `v` lines for every vertex (no-data vertices
+/// included, same as [`export_dtm_to_ply`], so face indices stay simple
+/// 1-based positions into the vertex list), `f` triangle lines for each
+/// face [`triangulate_grid`] kept, and -- when `with_uvs` is set -- a `vt`
+/// line per vertex mapping its grid position to `[0, 1]x[0, 1]`, letting a
+/// texture (e.g. this same raster exported as EXR/PNG) be applied via the
+/// faces' `f v/vt` indices.
+///
+/// Faces are only ever emitted over vertices `triangulate_grid` already
+/// kept (no no-data corners), so there's no face referencing a vertex that
+/// was skipped -- there's nothing to skip, every sampled cell gets a `v`
+/// line whether or not it's no-data.
+///
+/// `decimate`/`downsample_method` have the same meaning as in
+/// [`export_dtm_to_ply`]: a stride greater than `1` subsamples the grid with
+/// [`decimate_grid`] before triangulating and writing `vt` coordinates, so
+/// UVs stay aligned with the decimated vertex positions.
+///
+/// `weld_tolerance` has the same meaning as in [`export_dtm_to_ply`], except
+/// it's rejected together with `with_uvs`: welding changes the vertex count
+/// and order, which would desync the `vt` coordinates this function writes
+/// one-per-grid-cell.
+///
+/// `target_vertices` has the same meaning as in [`export_dtm_to_ply`]; it's
+/// similarly rejected together with `with_uvs`, since thinning faces over a
+/// vertex doesn't remove its `vt` line, but a consumer mapping UVs by face
+/// would see fewer faces referencing the flat areas' UVs than expected.
+///
+/// `flip_y` inverts the row coordinate -- see [`sample_mesh_grid`].
+#[allow(clippy::too_many_arguments)]
+pub fn export_dtm_to_obj(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    y_scale: f32,
+    window_scale_factor: usize,
+    overwrite: bool,
+    with_uvs: bool,
+    decimate: usize,
+    downsample_method: filters::DownsampleMethod,
+    weld_tolerance: Option<f32>,
+    target_vertices: Option<usize>,
+    flip_y: bool,
+) -> Result<PathBuf> {
+    if with_uvs && weld_tolerance.is_some() {
+        return Err(eyre!("--weld-tolerance is not supported together with --with-uvs"));
+    }
+    if with_uvs && target_vertices.is_some() {
+        return Err(eyre!("--target-vertices is not supported together with --with-uvs"));
+    }
+
+    let (points, is_nodata, raster_w, raster_h) = sample_mesh_grid(in_image_path, y_scale, window_scale_factor, flip_y)?;
+    let (points, is_nodata, raster_w, raster_h) =
+        decimate_grid(&points, &is_nodata, raster_w, raster_h, decimate, downsample_method);
+    let exclude = mesh_face_exclusion_mask(&points, &is_nodata, raster_w, raster_h, target_vertices);
+    let faces = triangulate_grid(&exclude, raster_w, raster_h);
+    let (points, faces) = match weld_tolerance {
+        Some(tolerance) => mesh::weld_vertices(&points, &faces, tolerance),
+        None => (points, faces),
+    };
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("obj");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    write_atomically(&output_path, |tmp_path| {
+        let file = std::fs::File::create(tmp_path)?;
+        let mut writer = io::BufWriter::new(file);
+
+        writeln!(writer, "# exported by gdal-dtm-exporter")?;
+        for point in &points {
+            writeln!(writer, "v {} {} {}", point.x, point.y, point.z)?;
+        }
+        if with_uvs {
+            for y in 0..raster_h {
+                for x in 0..raster_w {
+                    let u = if raster_w > 1 { x as f32 / (raster_w - 1) as f32 } else { 0.0 };
+                    let v = if raster_h > 1 { y as f32 / (raster_h - 1) as f32 } else { 0.0 };
+                    writeln!(writer, "vt {u} {v}")?;
+                }
+            }
+        }
+        for face in &faces {
+            // OBJ indices are 1-based.
+            if with_uvs {
+                writeln!(writer, "f {0}/{0} {1}/{1} {2}/{2}", face[0] + 1, face[1] + 1, face[2] + 1)?;
+            } else {
+                writeln!(writer, "f {} {} {}", face[0] + 1, face[1] + 1, face[2] + 1)?;
+            }
+        }
+
+        Ok(())
+    })?;
+
+    info!("wrote {} ({} vertices, {} faces)", output_path.display(), points.len(), faces.len());
+
+    Ok(output_path)
+}
+
+/// Writes `points`/`faces` (see [`sample_mesh_grid`]/[`triangulate_grid`]) as
+/// an ASCII USD (`.usda`) file: one `UsdGeomMesh` prim with `points`,
+/// `faceVertexCounts` (all `3`s, since [`triangulate_grid`] only ever emits
+/// triangles) and `faceVertexIndices` authored directly. Good enough for a
+/// DCC like Houdini to pull the mesh in; normals/UVs/binary `.usdc` can
+/// follow later if a consumer actually needs them.
+///
+/// `decimate`/`downsample_method`/`weld_tolerance`/`target_vertices`/
+/// `flip_y` have the same meaning as in [`export_dtm_to_ply`]/
+/// [`export_dtm_to_obj`].
+#[allow(clippy::too_many_arguments)]
+pub fn export_dtm_to_usda(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    y_scale: f32,
+    window_scale_factor: usize,
+    overwrite: bool,
+    decimate: usize,
+    downsample_method: filters::DownsampleMethod,
+    weld_tolerance: Option<f32>,
+    target_vertices: Option<usize>,
+    flip_y: bool,
+) -> Result<PathBuf> {
+    let (points, is_nodata, raster_w, raster_h) = sample_mesh_grid(in_image_path, y_scale, window_scale_factor, flip_y)?;
+    let (points, is_nodata, raster_w, raster_h) =
+        decimate_grid(&points, &is_nodata, raster_w, raster_h, decimate, downsample_method);
+    let exclude = mesh_face_exclusion_mask(&points, &is_nodata, raster_w, raster_h, target_vertices);
+    let faces = triangulate_grid(&exclude, raster_w, raster_h);
+    let (points, faces) = match weld_tolerance {
+        Some(tolerance) => mesh::weld_vertices(&points, &faces, tolerance),
+        None => (points, faces),
+    };
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("usda");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    write_atomically(&output_path, |tmp_path| {
+        let file = std::fs::File::create(tmp_path)?;
+        let mut writer = io::BufWriter::new(file);
+
+        let mesh_name = sanitize_usd_prim_name(stem.to_string_lossy().as_ref());
+
+        writeln!(writer, "#usda 1.0")?;
+        writeln!(writer, "(")?;
+        writeln!(writer, "    doc = \"exported by gdal-dtm-exporter\"")?;
+        writeln!(writer, ")")?;
+        writeln!(writer)?;
+        writeln!(writer, "def Mesh \"{mesh_name}\"")?;
+        writeln!(writer, "{{")?;
+
+        write!(writer, "    int[] faceVertexCounts = [")?;
+        for (i, _) in faces.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ", ")?;
+            }
+            write!(writer, "3")?;
+        }
+        writeln!(writer, "]")?;
+
+        write!(writer, "    int[] faceVertexIndices = [")?;
+        for (i, face) in faces.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ", ")?;
+            }
+            write!(writer, "{}, {}, {}", face[0], face[1], face[2])?;
+        }
+        writeln!(writer, "]")?;
+
+        write!(writer, "    point3f[] points = [")?;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ", ")?;
+            }
+            write!(writer, "({}, {}, {})", point.x, point.y, point.z)?;
+        }
+        writeln!(writer, "]")?;
+
+        writeln!(writer, "}}")?;
+
+        Ok(())
+    })?;
+
+    info!("wrote {} ({} vertices, {} faces)", output_path.display(), points.len(), faces.len());
+
+    Ok(output_path)
+}
+
+/// Sanitizes `name` into a valid USD prim name: ASCII letters/digits/
+/// underscore only, with anything else replaced by `_`, and a leading `_`
+/// added if the result would otherwise start with a digit (prim names can't).
+fn sanitize_usd_prim_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if sanitized.is_empty() {
+        sanitized.push('_');
+    }
+    if sanitized.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Reads `in_image_path` and normalizes it the same way [`export_dtm_to_exr`]
+/// would, but writes the result into an in-memory GDAL dataset (the `MEM`
+/// driver) instead of a file on disk, with the source geotransform and
+/// projection carried over.
+///
+/// This is meant for library consumers chaining the exporter into further
+/// GDAL processing (warping, mosaicking, ...) without a disk round-trip. The
+/// returned `Dataset` owns its pixel buffers; it is not backed by the input
+/// file and remains valid after `in_image_path` is dropped or closed.
+pub fn export_to_mem_dataset(in_image_path: &PathBuf, normalize: bool) -> Result<Dataset> {
+    let source = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = source.raster_size();
+    let band_count = source.raster_count();
+
+    let mem_driver = DriverManager::get_driver_by_name("MEM")?;
+    let mut mem_dataset =
+        mem_driver.create_with_band_type::<f32, _>("", raster_w, raster_h, band_count)?;
+
+    mem_dataset.set_geo_transform(&source.geo_transform()?)?;
+    mem_dataset.set_projection(&source.projection())?;
+
+    for band_index in 1..=band_count {
+        let src_band = source.rasterband(band_index)?;
+        check_supported_pixel_type(&src_band)?;
+        let stats = if normalize {
+            Some(band_min_max_excluding_nodata(&src_band, 0, 0, raster_w, raster_h)?)
+        } else {
+            None
+        };
+
+        let buffer = src_band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+        let mut data = buffer.data().to_vec();
+
+        if let Some((min, max)) = stats {
+            for value in data.iter_mut() {
+                *value = checked_map_range(*value as f64, (min, max), (0.0, 1.0)).unwrap_or(0.0) as f32;
+            }
+        }
+
+        let mut dst_band = mem_dataset.rasterband(band_index)?;
+        dst_band.write((0, 0), (raster_w, raster_h), &Buffer::new((raster_w, raster_h), data))?;
+    }
+
+    Ok(mem_dataset)
+}
+
+/// Writes every band of `in_image_path` into one multi-band GeoTIFF via
+/// GDAL's `GTiff` driver, preserving the source geotransform and CRS.
+///
+/// Unlike the EXR paths, this keeps multispectral/multi-temporal stacks
+/// together in a single file rather than collapsing bands into one RGB
+/// image or splitting them into separate files.
+///
+/// The output dataset's pixel type matches band 1's native type for
+/// `Byte`/`UInt16`/`Int16`/`UInt32`/`Int32` sources, instead of always
+/// casting through `f32` -- an integer DEM keeps its exact sentinel/no-data
+/// values and precision this way. Anything else (float bands, or a band
+/// type doesn't get rejected outright as unsupported) still goes through
+/// `f32`, same as before. A later band whose type doesn't match
+/// band 1's is read with a cast and a logged warning rather than failing
+/// the whole export.
+pub fn export_multiband_to_tiff(
+    in_image_path: &PathBuf,
+    export_dir: &PathBuf,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let source = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = source.raster_size();
+    let band_count = source.raster_count();
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let stem = in_image_path
+        .file_stem()
+        .ok_or_else(|| eyre!("input path has no file name"))?;
+    let output_path = export_dir.join(stem).with_extension("tif");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    let mut tmp_name = output_path.clone().into_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    // Reading everything through `f32` loses the no-data sentinel semantics
+    // of an Int16/UInt16 source (e.g. a sentinel like -32768 surviving the
+    // round trip exactly) and throws away precision an integer GeoTIFF
+    // reader expects back. Pick the output dataset's type to match band 1's
+    // native type for the common integer cases instead, falling back to the
+    // previous `f32` behavior for float/unrecognized types.
+    let band_type = source.rasterband(1)?.band_type();
+    info!("source band type: {band_type:?}");
+
+    macro_rules! write_native {
+        ($t:ty) => {{
+            let mut output_dataset =
+                tiff_driver.create_with_band_type::<$t, _>(&tmp_path, raster_w, raster_h, band_count)?;
+
+            output_dataset.set_geo_transform(&source.geo_transform()?)?;
+            output_dataset.set_projection(&source.projection())?;
+
+            for band_index in 1..=band_count {
+                let src_band = source.rasterband(band_index)?;
+                check_supported_pixel_type(&src_band)?;
+                if src_band.band_type() != band_type {
+                    log::warn!(
+                        "band {band_index} is {:?}, but band 1 ({:?}) decides this GeoTIFF's output type; values will be cast on read",
+                        src_band.band_type(),
+                        band_type
+                    );
+                }
+                let buffer = src_band.read_as::<$t>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+
+                let mut dst_band = output_dataset.rasterband(band_index)?;
+                dst_band.write((0, 0), (raster_w, raster_h), &Buffer::new((raster_w, raster_h), buffer.data().to_vec()))?;
+            }
+        }};
+    }
+
+    let write_result: Result<()> = (|| {
+        let tiff_driver = DriverManager::get_driver_by_name("GTiff")?;
+
+        use gdal::raster::GdalDataType;
+        match band_type {
+            GdalDataType::Byte => write_native!(u8),
+            GdalDataType::UInt16 => write_native!(u16),
+            GdalDataType::Int16 => write_native!(i16),
+            GdalDataType::UInt32 => write_native!(u32),
+            GdalDataType::Int32 => write_native!(i32),
+            _ => write_native!(f32),
+        }
+
+        Ok(())
+    })();
+
+    if let Err(err) = write_result {
+        std::fs::remove_file(&tmp_path).ok();
+        return Err(err);
+    }
+
+    std::fs::rename(&tmp_path, &output_path)?;
+
+    info!("wrote {} band(s) to {}", band_count, output_path.display());
+
+    Ok(output_path)
+}
+
+/// Reads three single-band rasters and packs them into one RGB OpenEXR image,
+/// one input per channel. Useful for combining elevation/slope/mask (or any
+/// three aligned single-band layers) into a single file for a shader.
+///
+/// All three inputs must share the same raster dimensions; use the first
+/// input's dimensions as the reference and error out otherwise.
+pub fn pack_channels_to_exr(
+    red_path: &PathBuf,
+    green_path: &PathBuf,
+    blue_path: &PathBuf,
+    export_dir: &PathBuf,
+    output_name: &str,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let red_dataset = Dataset::open(red_path)?;
+    let green_dataset = Dataset::open(green_path)?;
+    let blue_dataset = Dataset::open(blue_path)?;
+
+    let (raster_w, raster_h) = red_dataset.raster_size();
+
+    if green_dataset.raster_size() != (raster_w, raster_h)
+        || blue_dataset.raster_size() != (raster_w, raster_h)
+    {
+        return Err(eyre!(
+            "--red, --green and --blue must have matching dimensions (red is {}x{})",
+            raster_w,
+            raster_h
+        ));
+    }
+
+    let red_band = red_dataset.rasterband(1)?;
+    let green_band = green_dataset.rasterband(1)?;
+    let blue_band = blue_dataset.rasterband(1)?;
+
+    let red_data = red_band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+    let green_data =
+        green_band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+    let blue_data =
+        blue_band.read_as::<f32>((0, 0), (raster_w, raster_h), (raster_w, raster_h), None)?;
+
+    let mut output_image = Rgb32FImage::new(raster_w as u32, raster_h as u32);
+
+    for y in 0..raster_h {
+        for x in 0..raster_w {
+            let i = y * raster_w + x;
+            output_image.put_pixel(
+                x as u32,
+                y as u32,
+                Rgb([red_data.data()[i], green_data.data()[i], blue_data.data()[i]]),
+            );
+        }
+    }
+
+    std::fs::create_dir_all(export_dir)?;
+
+    let output_path = export_dir.join(output_name).with_extension("exr");
+
+    if !overwrite && !confirm_overwrite(&output_path)? {
+        return Err(DtmExportError::OutputExists(output_path.clone()).into());
+    }
+
+    write_atomically(&output_path, |tmp_path| Ok(output_image.save(tmp_path)?))?;
+
+    info!("wrote packed RGB {}", output_path.display());
+
+    Ok(output_path)
+}
+
+/// Quantifies how much dynamic range would be lost if `values` were written
+/// to an output type with `output_bits` bits per sample (e.g. 8 or 16 for
+/// integer formats, 10 for half-float's usable mantissa).
+#[derive(Debug, Clone, Copy)]
+pub struct QuantizationReport {
+    /// Fraction of the source's [min, max] range a single output step can
+    /// distinguish; lower is worse (more of the range collapses together).
+    pub representable_fraction: f64,
+    /// Count of distinct source values that would map to the same output
+    /// step as at least one other distinct source value.
+    pub collapsed_value_count: usize,
+}
+
+/// Computes a [`QuantizationReport`] for `values` against an `output_bits`-bit
+/// output type, based on the source's actual min/max (not the type's
+/// theoretical range).
+///
+/// This only reports the loss; it doesn't perform any conversion itself, so
+/// it's safe to call before deciding whether a constrained output type is
+/// even worth using.
+pub fn quantization_report(values: &[f32], output_bits: u32) -> QuantizationReport {
+    let levels = (1u64 << output_bits.min(63)) as f64;
+
+    let mut min = f32::INFINITY;
+    let mut max = f32::NEG_INFINITY;
+    for &v in values {
+        min = min.min(v);
+        max = max.max(v);
+    }
+
+    let range = (max - min) as f64;
+    if range <= 0.0 || values.is_empty() {
+        return QuantizationReport {
+            representable_fraction: 1.0,
+            collapsed_value_count: 0,
+        };
+    }
+
+    let step = range / levels;
+    let representable_fraction = (step / range).min(1.0);
+
+    let mut buckets = std::collections::HashSet::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut collapsed_value_count = 0;
+
+    for &v in values {
+        let bits = v.to_bits();
+        if !seen.insert(bits) {
+            continue;
+        }
+        let bucket = (((v - min) as f64 / step) as i64).min(levels as i64 - 1);
+        if !buckets.insert(bucket) {
+            collapsed_value_count += 1;
+        }
+    }
+
+    QuantizationReport {
+        representable_fraction,
+        collapsed_value_count,
+    }
+}
+
+/// Lists the raster files GDAL can see inside a zip archive, as `/vsizip/`
+/// paths ready to pass straight to [`describe_dataset`] or
+/// [`export_dtm_to_exr`].
+///
+/// Lets callers (namely the GUI) offer a zip file picker instead of
+/// requiring users to unzip their DTM downloads first.
+pub fn list_rasters_in_zip(zip_path: &PathBuf) -> Result<Vec<String>> {
+    let vsi_root = format!("/vsizip/{}", zip_path.display());
+
+    let entries = gdal::vsi::read_dir(&vsi_root, true)?;
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            matches!(
+                entry.extension().and_then(|ext| ext.to_str()),
+                Some("tif") | Some("tiff") | Some("img") | Some("asc") | Some("dem")
+            )
+        })
+        .map(|entry| format!("{}/{}", vsi_root, entry.display()))
+        .collect())
+}
+
+/// A downsampled elevation grid plus the georeferencing needed to map a
+/// grid cell back to world coordinates, for interactive preview in the GUI.
+#[derive(Debug, Clone)]
+pub struct PreviewGrid {
+    pub values: Vec<f32>,
+    pub width: usize,
+    pub height: usize,
+    pub elevation_min: f32,
+    pub elevation_max: f32,
+    /// Source raster dimensions, so preview pixel coords can be scaled back
+    /// up to full-resolution pixel coords.
+    pub source_width: usize,
+    pub source_height: usize,
+    pub geo_transform: [f64; 6],
+}
+
+impl PreviewGrid {
+    /// Converts a coordinate in this (decimated) preview grid to the
+    /// equivalent full-resolution source pixel coordinate.
+    pub fn preview_to_source_pixel(&self, preview_x: usize, preview_y: usize) -> (f64, f64) {
+        let scale_x = self.source_width as f64 / self.width as f64;
+        let scale_y = self.source_height as f64 / self.height as f64;
+        (preview_x as f64 * scale_x, preview_y as f64 * scale_y)
+    }
+
+    /// Converts a full-resolution source pixel coordinate to world
+    /// coordinates using the dataset's geotransform.
+    pub fn source_pixel_to_world(&self, px: f64, py: f64) -> (f64, f64) {
+        let gt = &self.geo_transform;
+        let x = gt[0] + px * gt[1] + py * gt[2];
+        let y = gt[3] + px * gt[4] + py * gt[5];
+        (x, y)
+    }
+}
+
+/// Reads band 1 of `in_image_path` downsampled so neither dimension exceeds
+/// `max_dimension`, for a responsive GUI preview.
+///
+/// The GUI loads this once per picked file and then does all pan/zoom/
+/// elevation-readout work against it in memory, rather than re-reading GDAL
+/// on every frame.
+pub fn build_preview_grid(in_image_path: &PathBuf, max_dimension: usize) -> Result<PreviewGrid> {
+    let dataset = Dataset::open(in_image_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let geo_transform = dataset.geo_transform()?;
+    let band = dataset.rasterband(1)?;
+    check_supported_pixel_type(&band)?;
+    let stats = band.compute_raster_min_max(true)?;
+
+    let scale = (raster_w.max(raster_h) as f64 / max_dimension as f64).max(1.0);
+    let preview_w = ((raster_w as f64 / scale).round() as usize).max(1);
+    let preview_h = ((raster_h as f64 / scale).round() as usize).max(1);
+
+    let buffer = band.read_as::<f32>(
+        (0, 0),
+        (raster_w, raster_h),
+        (preview_w, preview_h),
+        Some(ResampleAlg::Bilinear),
+    )?;
+
+    Ok(PreviewGrid {
+        values: buffer.data().to_vec(),
+        width: preview_w,
+        height: preview_h,
+        elevation_min: stats.min as f32,
+        elevation_max: stats.max as f32,
+        source_width: raster_w,
+        source_height: raster_h,
+        geo_transform,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_range_scales_linearly() {
+        assert_eq!(map_range(0.5_f64, (0.0, 1.0), (0.0, 10.0)), 5.0);
+        assert_eq!(map_range(0.0_f64, (0.0, 1.0), (0.0, 10.0)), 0.0);
+        assert_eq!(map_range(1.0_f64, (0.0, 1.0), (0.0, 10.0)), 10.0);
+    }
+
+    #[test]
+    fn map_range_handles_inverted_to_range() {
+        assert_eq!(map_range(0.25_f64, (0.0, 1.0), (10.0, 0.0)), 7.5);
+    }
+
+    #[test]
+    fn map_range_divides_by_zero_on_flat_from_range() {
+        assert!(map_range(0.5_f64, (1.0, 1.0), (0.0, 10.0)).is_nan());
+    }
+
+    #[test]
+    fn checked_map_range_matches_map_range_for_normal_ranges() {
+        assert_eq!(checked_map_range(0.5_f64, (0.0, 1.0), (0.0, 10.0)), Some(5.0));
+    }
+
+    #[test]
+    fn checked_map_range_returns_none_for_flat_from_range() {
+        assert_eq!(checked_map_range(0.5_f64, (1.0, 1.0), (0.0, 10.0)), None);
+    }
+
+    #[test]
+    fn denormalize_inverts_the_zero_to_one_normalization() {
+        assert_eq!(denormalize(0.5, 0.0, 100.0), 50.0);
+        assert_eq!(denormalize(0.0, -10.0, 10.0), -10.0);
+        assert_eq!(denormalize(1.0, -10.0, 10.0), 10.0);
+    }
+}
@@ -0,0 +1,225 @@
+//! Shared mesh-building helpers used by the PLY/OBJ exporters.
+
+use std::collections::HashMap;
+
+/// A mesh vertex, in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+fn quantize(value: f32, tolerance: f32) -> i64 {
+    (value / tolerance).round() as i64
+}
+
+/// Merges vertices that land within `tolerance` of each other (by quantized
+/// world position) and re-indexes `faces` to point at the merged set.
+///
+/// Meant for stitching tiled mesh output, where shared edge vertices would
+/// otherwise be duplicated across tiles, producing cracks at the seams.
+pub fn weld_vertices(
+    vertices: &[Vertex],
+    faces: &[[usize; 3]],
+    tolerance: f32,
+) -> (Vec<Vertex>, Vec<[usize; 3]>) {
+    let mut welded = Vec::new();
+    let mut remap = vec![0usize; vertices.len()];
+    let mut seen: HashMap<(i64, i64, i64), usize> = HashMap::new();
+
+    for (i, vertex) in vertices.iter().enumerate() {
+        let key = (
+            quantize(vertex.x, tolerance),
+            quantize(vertex.y, tolerance),
+            quantize(vertex.z, tolerance),
+        );
+
+        let welded_index = *seen.entry(key).or_insert_with(|| {
+            welded.push(*vertex);
+            welded.len() - 1
+        });
+
+        remap[i] = welded_index;
+    }
+
+    let welded_faces = faces
+        .iter()
+        .map(|face| [remap[face[0]], remap[face[1]], remap[face[2]]])
+        .collect();
+
+    (welded, welded_faces)
+}
+
+/// Computes the surface normal at `(x, y)` in a `width`x`height` height
+/// grid from its elevation gradient. The PLY/OBJ exporters don't call it
+/// and write no per-vertex normals.
+///
+/// Uses a central difference where both neighbors are available and falls
+/// back to a one-sided difference at the grid border, so edge vertices get
+/// a real (if less accurate) normal instead of a degenerate all-zero one.
+pub fn vertex_normal(heights: &[f32], width: usize, height: usize, x: usize, y: usize) -> [f32; 3] {
+    let at = |x: usize, y: usize| heights[y * width + x];
+
+    let dzdx = if x == 0 {
+        at(x + 1, y) - at(x, y)
+    } else if x + 1 >= width {
+        at(x, y) - at(x - 1, y)
+    } else {
+        (at(x + 1, y) - at(x - 1, y)) / 2.0
+    };
+
+    let dzdy = if y == 0 {
+        at(x, y + 1) - at(x, y)
+    } else if y + 1 >= height {
+        at(x, y) - at(x, y - 1)
+    } else {
+        (at(x, y + 1) - at(x, y - 1)) / 2.0
+    };
+
+    let normal = [-dzdx, -dzdy, 1.0];
+    let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+
+    [normal[0] / length, normal[1] / length, normal[2] / length]
+}
+
+/// Estimates local curvature at `(x, y)` in a `width`x`height` height grid
+/// as the absolute deviation of the center sample from the average of its
+/// four cardinal neighbors. Zero at the grid border, where there's no full
+/// neighborhood to compare against.
+fn curvature_at(heights: &[f32], width: usize, height: usize, x: usize, y: usize) -> f32 {
+    if x == 0 || y == 0 || x + 1 >= width || y + 1 >= height {
+        return 0.0;
+    }
+
+    let center = heights[y * width + x];
+    let neighbor_avg = (heights[y * width + x - 1]
+        + heights[y * width + x + 1]
+        + heights[(y - 1) * width + x]
+        + heights[(y + 1) * width + x])
+        / 4.0;
+
+    (center - neighbor_avg).abs()
+}
+
+/// Picks which grid cells to keep for mesh export so that high-curvature
+/// terrain (ridges, gullies) stays dense while flat areas get thinned,
+/// targeting `target_vertices` total kept cells.
+///
+/// Always keeps the grid border (needed to avoid holes at tile edges); the
+/// interior budget is filled by curvature, highest first. Returns a
+/// `width`x`height` keep-mask the same shape as `heights`.
+pub fn adaptive_decimation_mask(
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    target_vertices: usize,
+) -> Vec<bool> {
+    let mut mask = vec![false; width * height];
+
+    let mut border_count = 0;
+    for y in 0..height {
+        for x in 0..width {
+            if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+                mask[y * width + x] = true;
+                border_count += 1;
+            }
+        }
+    }
+
+    let interior_budget = target_vertices.saturating_sub(border_count);
+
+    let mut interior: Vec<(usize, usize, f32)> = Vec::new();
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let curvature = curvature_at(heights, width, height, x, y);
+            interior.push((x, y, curvature));
+        }
+    }
+
+    interior.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    for &(x, y, _) in interior.iter().take(interior_budget) {
+        mask[y * width + x] = true;
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weld_vertices_merges_duplicates_within_tolerance() {
+        let vertices = [
+            Vertex { x: 0.0, y: 0.0, z: 0.0 },
+            Vertex { x: 1.0, y: 0.0, z: 0.0 },
+            // Duplicate of vertex 0, off by less than the tolerance.
+            Vertex { x: 0.0001, y: 0.0, z: 0.0 },
+            Vertex { x: 0.0, y: 1.0, z: 0.0 },
+        ];
+        let faces = [[0, 1, 3], [2, 1, 3]];
+
+        let (welded, welded_faces) = weld_vertices(&vertices, &faces, 0.01);
+
+        assert_eq!(welded.len(), 3, "vertices 0 and 2 should have merged into one");
+        // Both faces should now point at the same welded vertex for what
+        // were originally vertices 0 and 2.
+        assert_eq!(welded_faces[0][0], welded_faces[1][0]);
+        for face in &welded_faces {
+            for &index in face {
+                assert!(index < welded.len());
+            }
+        }
+    }
+
+    #[test]
+    fn weld_vertices_leaves_distinct_vertices_alone() {
+        let vertices = [
+            Vertex { x: 0.0, y: 0.0, z: 0.0 },
+            Vertex { x: 10.0, y: 0.0, z: 0.0 },
+            Vertex { x: 0.0, y: 10.0, z: 0.0 },
+        ];
+        let faces = [[0, 1, 2]];
+
+        let (welded, welded_faces) = weld_vertices(&vertices, &faces, 0.01);
+
+        assert_eq!(welded.len(), vertices.len());
+        assert_eq!(welded_faces, faces);
+    }
+
+    #[test]
+    fn adaptive_decimation_mask_always_keeps_the_border() {
+        let width = 5;
+        let height = 5;
+        let heights = vec![0.0f32; width * height];
+
+        // A target smaller than the border count still shouldn't drop any
+        // border cell -- only the interior budget saturates to zero.
+        let mask = adaptive_decimation_mask(&heights, width, height, 1);
+
+        for y in 0..height {
+            for x in 0..width {
+                if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+                    assert!(mask[y * width + x], "border cell ({x}, {y}) should always be kept");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_decimation_mask_prefers_higher_curvature_interior_cells() {
+        let width = 5;
+        let height = 5;
+        // Flat except for one interior spike at (2, 2), which should be the
+        // first interior cell kept once the border budget is spent.
+        let mut heights = vec![0.0f32; width * height];
+        heights[2 * width + 2] = 100.0;
+
+        let border_count = 2 * width + 2 * (height - 2);
+        let mask = adaptive_decimation_mask(&heights, width, height, border_count + 1);
+
+        assert!(mask[2 * width + 2], "the only high-curvature interior cell should be kept first");
+    }
+}
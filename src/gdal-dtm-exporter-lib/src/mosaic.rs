@@ -0,0 +1,219 @@
+//! Compositing several adjacent DTM tiles into one raster ahead of export,
+//! so a caller with a pre-tiled survey area doesn't have to run
+//! `gdalbuildvrt` (or merge the tiles by hand) before reaching for
+//! [`crate::export_dtm_to_exr`].
+
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Result};
+use gdal::raster::Buffer;
+use gdal::{Dataset, DriverManager};
+use log::{info, warn};
+
+/// Composites `paths` (all assumed to already share one CRS) into a single
+/// raster, placed according to each tile's own geotransform, and writes the
+/// result to a temporary GeoTIFF, returning its path. `paths` must have at
+/// least one entry; a single path is accepted and passed through a
+/// (degenerate, one-tile) mosaic rather than rejected, so a caller doesn't
+/// need to special-case the single-input case itself.
+///
+/// Every tile after the first is checked against the first tile's band
+/// count and pixel size: a mismatched band count is an error (there's no
+/// sane way to composite a 1-band and a 3-band tile into one raster), while
+/// a mismatched pixel size only warns and proceeds, resampling that tile
+/// to the first tile's resolution via nearest-neighbor placement -- the
+/// same per-destination-pixel nearest-source-pixel lookup
+/// [`crate::reproject::reproject_to_temp_geotiff`] uses, since both are
+/// mapping one grid onto another without the smoothing a real export's
+/// `ResampleAlg` choice would apply.
+///
+/// Tiles whose geotransform has a rotation/shear term (`geo_transform[2]` or
+/// `geo_transform[4]` non-zero) are rejected -- placing a rotated tile into
+/// an axis-aligned composite isn't a simple offset copy.
+///
+/// When two tiles' footprints overlap, the later tile (in `paths` order)
+/// wins over pixels the earlier one already wrote a valid (non-no-data)
+/// value into; this is logged once per overlapping pair rather than per
+/// pixel, since a caller mosaicking a real multi-tile survey expects
+/// adjacent tiles to touch at their edges and doesn't need a flood of
+/// per-pixel noise for it.
+pub fn mosaic_to_temp_geotiff(paths: &[PathBuf]) -> Result<PathBuf> {
+    if paths.is_empty() {
+        return Err(eyre!("mosaic requires at least one input path"));
+    }
+
+    let reference = Dataset::open(&paths[0])?;
+    let band_count = reference.raster_count();
+    let reference_srs = reference.spatial_ref().ok();
+    let reference_transform = reference.geo_transform()?;
+    check_axis_aligned(&reference_transform, &paths[0])?;
+    let (pixel_width, pixel_height) = (reference_transform[1], reference_transform[5]);
+
+    struct Tile {
+        path: PathBuf,
+        transform: [f64; 6],
+        size: (usize, usize),
+    }
+
+    let mut tiles = vec![Tile { path: paths[0].clone(), transform: reference_transform, size: reference.raster_size() }];
+
+    for path in &paths[1..] {
+        let dataset = Dataset::open(path)?;
+        if dataset.raster_count() != band_count {
+            return Err(eyre!(
+                "{} has {} band(s), expected {band_count} to match {}",
+                path.display(),
+                dataset.raster_count(),
+                paths[0].display()
+            ));
+        }
+
+        let transform = dataset.geo_transform()?;
+        check_axis_aligned(&transform, path)?;
+
+        let resolution_mismatch =
+            relative_diff(transform[1], pixel_width) > 0.01 || relative_diff(transform[5], pixel_height) > 0.01;
+        if resolution_mismatch {
+            warn!(
+                "{} has pixel size ({}, {}), which doesn't match {}'s ({pixel_width}, {pixel_height}); \
+                 placing it with nearest-neighbor resampling to the first tile's resolution",
+                path.display(),
+                transform[1],
+                transform[5],
+                paths[0].display()
+            );
+        }
+
+        tiles.push(Tile { path: path.clone(), transform, size: dataset.raster_size() });
+    }
+
+    // Union bounding box, in world units, across every tile's own footprint.
+    let mut min_x = f64::INFINITY;
+    let mut max_x = f64::NEG_INFINITY;
+    let mut min_y = f64::INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
+    for tile in &tiles {
+        let (w, h) = tile.size;
+        let corners = [(0.0, 0.0), (w as f64, 0.0), (0.0, h as f64), (w as f64, h as f64)];
+        for (px, py) in corners {
+            let wx = tile.transform[0] + px * tile.transform[1];
+            let wy = tile.transform[3] + py * tile.transform[5];
+            min_x = min_x.min(wx);
+            max_x = max_x.max(wx);
+            min_y = min_y.min(wy);
+            max_y = max_y.max(wy);
+        }
+    }
+
+    let dst_w = ((max_x - min_x) / pixel_width).round().max(1.0) as usize;
+    let dst_h = ((max_y - min_y) / pixel_height.abs()).round().max(1.0) as usize;
+    let dst_transform = [min_x, pixel_width, 0.0, max_y, 0.0, pixel_height];
+
+    let mut band_nodata: Vec<Option<f64>> = vec![None; band_count];
+    let mut band_data: Vec<Vec<f32>> = (0..band_count).map(|_| vec![f32::NAN; dst_w * dst_h]).collect();
+    let mut written_by: Vec<Option<usize>> = vec![None; dst_w * dst_h];
+    let mut warned_pairs: std::collections::HashSet<(usize, usize)> = std::collections::HashSet::new();
+
+    for (tile_index, tile) in tiles.iter().enumerate() {
+        let dataset = Dataset::open(&tile.path)?;
+        let (src_w, src_h) = tile.size;
+
+        let px_off = ((tile.transform[0] - dst_transform[0]) / pixel_width).round() as isize;
+        let py_off = ((tile.transform[3] - dst_transform[3]) / pixel_height).round() as isize;
+
+        let needs_resample = tile.transform[1] != pixel_width || tile.transform[5] != pixel_height;
+
+        for band_index in 1..=band_count {
+            let band = dataset.rasterband(band_index)?;
+            let nodata = band.no_data_value();
+            if band_nodata[band_index - 1].is_none() {
+                band_nodata[band_index - 1] = nodata;
+            }
+
+            let buffer = band.read_as::<f32>((0, 0), (src_w, src_h), (src_w, src_h), None)?;
+            let src_data = buffer.data();
+            let dst_data = &mut band_data[band_index - 1];
+
+            for dy in 0..dst_h {
+                for dx in 0..dst_w {
+                    let (sx, sy) = if needs_resample {
+                        let wx = dst_transform[0] + (dx as f64 + 0.5) * dst_transform[1];
+                        let wy = dst_transform[3] + (dy as f64 + 0.5) * dst_transform[5];
+                        let sx = ((wx - tile.transform[0]) / tile.transform[1]).floor() as isize;
+                        let sy = ((wy - tile.transform[3]) / tile.transform[5]).floor() as isize;
+                        (sx, sy)
+                    } else {
+                        (dx as isize - px_off, dy as isize - py_off)
+                    };
+
+                    if sx < 0 || sy < 0 || sx as usize >= src_w || sy as usize >= src_h {
+                        continue;
+                    }
+
+                    let value = src_data[sy as usize * src_w + sx as usize];
+                    if let Some(nd) = nodata {
+                        if value as f64 == nd {
+                            continue;
+                        }
+                    }
+
+                    let dst_index = dy * dst_w + dx;
+                    if let Some(previous_tile) = written_by[dst_index] {
+                        if previous_tile != tile_index && warned_pairs.insert((previous_tile, tile_index)) {
+                            warn!(
+                                "{} overlaps an earlier tile ({}); the later tile wins at the shared pixels",
+                                tile.path.display(),
+                                tiles[previous_tile].path.display()
+                            );
+                        }
+                    }
+
+                    dst_data[dst_index] = value;
+                    written_by[dst_index] = Some(tile_index);
+                }
+            }
+        }
+    }
+
+    let output_path = std::env::temp_dir().join(format!("mosaic-{}.tif", std::process::id()));
+
+    let tiff_driver = DriverManager::get_driver_by_name("GTiff")?;
+    let mut dst_dataset = tiff_driver.create_with_band_type::<f32, _>(&output_path, dst_w, dst_h, band_count)?;
+    dst_dataset.set_geo_transform(&dst_transform)?;
+    if let Some(srs) = &reference_srs {
+        dst_dataset.set_spatial_ref(srs)?;
+    }
+
+    for (band_slot, data) in band_data.into_iter().enumerate() {
+        let fill = band_nodata[band_slot].unwrap_or(0.0);
+        let data: Vec<f32> = data.into_iter().map(|v| if v.is_nan() { fill as f32 } else { v }).collect();
+
+        let mut dst_band = dst_dataset.rasterband(band_slot + 1)?;
+        dst_band.write((0, 0), (dst_w, dst_h), &Buffer::new((dst_w, dst_h), data))?;
+        if let Some(nodata) = band_nodata[band_slot] {
+            dst_band.set_no_data_value(nodata)?;
+        }
+    }
+
+    drop(dst_dataset);
+    info!("mosaicked {} tile(s) into {}x{} at {}", tiles.len(), dst_w, dst_h, output_path.display());
+
+    Ok(output_path)
+}
+
+fn check_axis_aligned(transform: &[f64; 6], path: &Path) -> Result<()> {
+    if transform[2] != 0.0 || transform[4] != 0.0 {
+        return Err(eyre!(
+            "{} has a rotated/sheared geotransform, which mosaicking doesn't support",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+fn relative_diff(a: f64, b: f64) -> f64 {
+    if b == 0.0 {
+        return if a == 0.0 { 0.0 } else { f64::INFINITY };
+    }
+    ((a - b) / b).abs()
+}
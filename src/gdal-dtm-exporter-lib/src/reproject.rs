@@ -0,0 +1,218 @@
+//! Options controlling the output grid of a reprojected export, and the
+//! warp itself.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Result};
+use gdal::raster::Buffer;
+use gdal::spatial_ref::{CoordTransform, SpatialRef};
+use gdal::{Dataset, DriverManager};
+use log::info;
+
+/// User-controlled knobs for a `--target-srs` warp: without these the output
+/// covers the source's reprojected extent at roughly the source's own pixel
+/// density, which often doesn't line up with what the caller actually needs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReprojectOptions {
+    /// Pixel size in target CRS units, as `(x, y)`.
+    pub target_resolution: Option<(f64, f64)>,
+    /// Output bounds in target CRS units, as `(min_x, min_y, max_x, max_y)`.
+    pub target_extent: Option<(f64, f64, f64, f64)>,
+}
+
+impl ReprojectOptions {
+    /// Validates that a supplied resolution is positive and a supplied
+    /// extent is non-degenerate.
+    pub fn validate(&self) -> Result<()> {
+        if let Some((x, y)) = self.target_resolution {
+            if x <= 0.0 || y <= 0.0 {
+                return Err(eyre!("--target-resolution values must be positive, got ({x}, {y})"));
+            }
+        }
+
+        if let Some((min_x, min_y, max_x, max_y)) = self.target_extent {
+            if max_x <= min_x || max_y <= min_y {
+                return Err(eyre!(
+                    "--target-extent is degenerate: ({min_x}, {min_y}, {max_x}, {max_y})"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes the output raster dimensions implied by `target_extent` and
+    /// `target_resolution`, when both are specified.
+    pub fn output_dimensions(&self) -> Option<(usize, usize)> {
+        let (min_x, min_y, max_x, max_y) = self.target_extent?;
+        let (res_x, res_y) = self.target_resolution?;
+
+        let width = ((max_x - min_x) / res_x).ceil() as usize;
+        let height = ((max_y - min_y) / res_y).ceil() as usize;
+
+        Some((width, height))
+    }
+}
+
+/// Reprojects `source_path` into `target_srs` (an EPSG code like
+/// `"EPSG:4326"`, a WKT string, or anything else `gdal::spatial_ref::SpatialRef`
+/// accepts as user input) and writes the result to a temporary GeoTIFF,
+/// returning its path. Returns `Ok(None)` (skipping the warp entirely)
+/// when `source_path` is already in `target_srs`.
+///
+/// The destination grid defaults to covering the source raster's
+/// reprojected extent at roughly the source's own pixel density, unless
+/// `options` overrides the resolution and/or extent.
+///
+/// Resamples nearest-neighbor: for every destination pixel, the inverse
+/// transform locates the nearest source pixel directly rather than
+/// averaging/interpolating across several, unlike the `ResampleAlg` choices
+/// [`crate::export_dtm_to_exr`] offers for its own (axis-aligned) tile
+/// reads. Good enough for a one-time CRS conversion ahead of the real
+/// export; a caller that needs smoother resampling across a reprojection
+/// should resample again downstream.
+///
+/// Reads every source band fully into memory up front, the same one-shot
+/// whole-band read [`crate::export_multiband_to_tiff`]'s normalization pass
+/// already uses -- reasonable here too since this runs once per export, not
+/// once per tile.
+pub fn reproject_to_temp_geotiff(source_path: &Path, target_srs: &str, options: &ReprojectOptions) -> Result<Option<PathBuf>> {
+    options.validate()?;
+
+    let source = Dataset::open(source_path)?;
+    let src_srs = source
+        .spatial_ref()
+        .map_err(|err| eyre!("source dataset has no usable CRS to reproject from: {err}"))?;
+    let dst_srs = SpatialRef::from_user_input(target_srs)
+        .map_err(|err| eyre!("invalid --target-srs {target_srs:?}: {err}"))?;
+
+    let src_name = src_srs.name().unwrap_or_else(|_| "<unnamed>".to_string());
+    let dst_name = dst_srs.name().unwrap_or_else(|_| "<unnamed>".to_string());
+
+    if src_srs.is_same(&dst_srs) {
+        info!("--target-srs ({dst_name}) matches the source CRS ({src_name}); skipping reprojection");
+        return Ok(None);
+    }
+
+    info!("reprojecting from {src_name} to {dst_name}");
+
+    let (src_w, src_h) = source.raster_size();
+    let src_geo_transform = source.geo_transform()?;
+    let band_count = source.raster_count();
+
+    let forward = CoordTransform::new(&src_srs, &dst_srs)
+        .map_err(|err| eyre!("failed to build a source -> target coordinate transform: {err}"))?;
+    let inverse = CoordTransform::new(&dst_srs, &src_srs)
+        .map_err(|err| eyre!("failed to build a target -> source coordinate transform: {err}"))?;
+
+    let pixel_to_world = |px: f64, py: f64| -> (f64, f64) {
+        (
+            src_geo_transform[0] + px * src_geo_transform[1] + py * src_geo_transform[2],
+            src_geo_transform[3] + px * src_geo_transform[4] + py * src_geo_transform[5],
+        )
+    };
+    let corners = [
+        pixel_to_world(0.0, 0.0),
+        pixel_to_world(src_w as f64, 0.0),
+        pixel_to_world(0.0, src_h as f64),
+        pixel_to_world(src_w as f64, src_h as f64),
+    ];
+    let mut xs: Vec<f64> = corners.iter().map(|(x, _)| *x).collect();
+    let mut ys: Vec<f64> = corners.iter().map(|(_, y)| *y).collect();
+    let mut zs = vec![0.0; xs.len()];
+    forward
+        .transform_coords(&mut xs, &mut ys, &mut zs)
+        .map_err(|err| eyre!("failed to project the raster extent into the target CRS: {err}"))?;
+
+    let computed_extent = (
+        xs.iter().cloned().fold(f64::INFINITY, f64::min),
+        ys.iter().cloned().fold(f64::INFINITY, f64::min),
+        xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+    );
+    let (min_x, min_y, max_x, max_y) = options.target_extent.unwrap_or(computed_extent);
+
+    let (dst_w, dst_h) = options.output_dimensions().unwrap_or_else(|| {
+        let (res_x, res_y) = options
+            .target_resolution
+            .unwrap_or(((max_x - min_x) / src_w as f64, (max_y - min_y) / src_h as f64));
+        ((((max_x - min_x) / res_x).ceil() as usize).max(1), (((max_y - min_y) / res_y).ceil() as usize).max(1))
+    });
+
+    let pixel_width = (max_x - min_x) / dst_w as f64;
+    let pixel_height = (max_y - min_y) / dst_h as f64;
+    let dst_geo_transform = [min_x, pixel_width, 0.0, max_y, 0.0, -pixel_height];
+
+    let dst_pixel_to_world = |px: f64, py: f64| -> (f64, f64) {
+        (
+            dst_geo_transform[0] + px * dst_geo_transform[1],
+            dst_geo_transform[3] + py * dst_geo_transform[5],
+        )
+    };
+
+    let src_bands: Vec<(Vec<f32>, Option<f64>)> = (1..=band_count)
+        .map(|band_index| -> Result<(Vec<f32>, Option<f64>)> {
+            let band = source.rasterband(band_index)?;
+            let buffer = band.read_as::<f32>((0, 0), (src_w, src_h), (src_w, src_h), None)?;
+            Ok((buffer.data().to_vec(), band.no_data_value()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let det = src_geo_transform[1] * src_geo_transform[5] - src_geo_transform[2] * src_geo_transform[4];
+    if det.abs() < f64::EPSILON {
+        return Err(eyre!("source dataset's geotransform is singular; can't reproject"));
+    }
+
+    let mut dst_xs = Vec::with_capacity(dst_w * dst_h);
+    let mut dst_ys = Vec::with_capacity(dst_w * dst_h);
+    for dy in 0..dst_h {
+        for dx in 0..dst_w {
+            let (wx, wy) = dst_pixel_to_world(dx as f64 + 0.5, dy as f64 + 0.5);
+            dst_xs.push(wx);
+            dst_ys.push(wy);
+        }
+    }
+    let mut dst_zs = vec![0.0; dst_xs.len()];
+    inverse
+        .transform_coords(&mut dst_xs, &mut dst_ys, &mut dst_zs)
+        .map_err(|err| eyre!("failed to transform destination pixels back into the source CRS: {err}"))?;
+
+    let output_path = std::env::temp_dir().join(format!(
+        "{}-reprojected-{}.tif",
+        source_path.file_stem().and_then(|s| s.to_str()).unwrap_or("dtm"),
+        std::process::id()
+    ));
+
+    let tiff_driver = DriverManager::get_driver_by_name("GTiff")?;
+    let mut dst_dataset = tiff_driver.create_with_band_type::<f32, _>(&output_path, dst_w, dst_h, band_count)?;
+    dst_dataset.set_geo_transform(&dst_geo_transform)?;
+    dst_dataset.set_spatial_ref(&dst_srs)?;
+
+    for (band_slot, (src_data, nodata)) in src_bands.iter().enumerate() {
+        let mut dst_data = vec![nodata.unwrap_or(0.0) as f32; dst_w * dst_h];
+
+        for i in 0..dst_xs.len() {
+            let dx_world = dst_xs[i] - src_geo_transform[0];
+            let dy_world = dst_ys[i] - src_geo_transform[3];
+            let src_px = (src_geo_transform[5] * dx_world - src_geo_transform[2] * dy_world) / det;
+            let src_py = (src_geo_transform[1] * dy_world - src_geo_transform[4] * dx_world) / det;
+
+            let sx = src_px.floor();
+            let sy = src_py.floor();
+            if sx >= 0.0 && sy >= 0.0 && (sx as usize) < src_w && (sy as usize) < src_h {
+                dst_data[i] = src_data[sy as usize * src_w + sx as usize];
+            }
+        }
+
+        let mut dst_band = dst_dataset.rasterband(band_slot + 1)?;
+        dst_band.write((0, 0), (dst_w, dst_h), &Buffer::new((dst_w, dst_h), dst_data))?;
+        if let Some(nodata) = nodata {
+            dst_band.set_no_data_value(*nodata)?;
+        }
+    }
+
+    drop(dst_dataset);
+    info!("wrote reprojected temp dataset to {}", output_path.display());
+
+    Ok(Some(output_path))
+}
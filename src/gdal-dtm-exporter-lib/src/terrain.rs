@@ -0,0 +1,91 @@
+//! Per-pixel terrain derivatives (hillshade, slope, aspect) computed from a
+//! height grid via Horn's 3x3 gradient estimator -- the same weighting
+//! `gdaldem` uses, which blends in diagonal neighbors for a steadier
+//! gradient than the central-difference pair [`crate::mesh::vertex_normal`]
+//! uses for per-vertex mesh normals.
+
+/// Units [`slope_from_gradient`] reports in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlopeUnits {
+    Degrees,
+    Percent,
+}
+
+/// Horn's 3x3 weighted gradient at `(x, y)` in a `width`x`height` height
+/// grid, in height units per ground unit. `pixel_size_x`/`pixel_size_y` are
+/// the ground distance (from the source geotransform) one pixel covers
+/// along each axis and should both be positive.
+///
+/// At the grid border, where the 3x3 kernel would read past the edge,
+/// out-of-range samples clamp to the nearest in-bounds row/column -- so a
+/// border pixel's gradient reuses its nearest real neighbor's contribution
+/// instead of treating the edge of the raster as a cliff down to zero.
+pub fn horn_gradient(
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    pixel_size_x: f64,
+    pixel_size_y: f64,
+) -> (f64, f64) {
+    let at = |dx: isize, dy: isize| -> f64 {
+        let sx = (x as isize + dx).clamp(0, width as isize - 1) as usize;
+        let sy = (y as isize + dy).clamp(0, height as isize - 1) as usize;
+        heights[sy * width + sx] as f64
+    };
+
+    let dzdx = ((at(1, -1) + 2.0 * at(1, 0) + at(1, 1)) - (at(-1, -1) + 2.0 * at(-1, 0) + at(-1, 1)))
+        / (8.0 * pixel_size_x);
+    let dzdy = ((at(-1, 1) + 2.0 * at(0, 1) + at(1, 1)) - (at(-1, -1) + 2.0 * at(0, -1) + at(1, -1)))
+        / (8.0 * pixel_size_y);
+
+    (dzdx, dzdy)
+}
+
+/// Classic Lambertian hillshade from a Horn gradient, given a sun
+/// `azimuth_deg` (0 = north, clockwise) and `altitude_deg` (0 = horizon, 90 =
+/// straight overhead). `z_factor` exaggerates the gradient the same way
+/// vertical exaggeration would, without having to rescale the source
+/// heights first. Returns a grayscale intensity in `[0, 1]`.
+pub fn hillshade_from_gradient(dzdx: f64, dzdy: f64, azimuth_deg: f64, altitude_deg: f64, z_factor: f64) -> f32 {
+    let zenith_rad = (90.0 - altitude_deg).to_radians();
+    // atan2's convention (0 = east, counter-clockwise) differs from compass
+    // azimuth (0 = north, clockwise), hence the 90-degree rotation here.
+    let azimuth_rad = (360.0 - azimuth_deg + 90.0).to_radians();
+
+    let slope_rad = (z_factor * (dzdx * dzdx + dzdy * dzdy).sqrt()).atan();
+    let aspect_rad = dzdy.atan2(-dzdx);
+
+    let shaded =
+        zenith_rad.cos() * slope_rad.cos() + zenith_rad.sin() * slope_rad.sin() * (azimuth_rad - aspect_rad).cos();
+
+    shaded.clamp(0.0, 1.0) as f32
+}
+
+/// Slope magnitude from a Horn gradient, in the units `units` picks.
+/// `Degrees` is the angle of the surface from horizontal; `Percent` is rise
+/// over run (can exceed 100 on a cliff face).
+pub fn slope_from_gradient(dzdx: f64, dzdy: f64, units: SlopeUnits) -> f32 {
+    let rise_over_run = (dzdx * dzdx + dzdy * dzdy).sqrt();
+
+    match units {
+        SlopeUnits::Degrees => rise_over_run.atan().to_degrees() as f32,
+        SlopeUnits::Percent => (rise_over_run * 100.0) as f32,
+    }
+}
+
+/// Downslope-facing direction from a Horn gradient, in compass degrees
+/// (0 = north, 90 = east, clockwise). Flat ground (both gradient components
+/// zero) has no defined downslope direction and reports `-1.0`, matching the
+/// sentinel `gdaldem aspect` uses for the same case.
+pub fn aspect_from_gradient(dzdx: f64, dzdy: f64) -> f32 {
+    if dzdx == 0.0 && dzdy == 0.0 {
+        return -1.0;
+    }
+
+    let aspect_rad = dzdy.atan2(-dzdx);
+    let aspect_deg = 90.0 - aspect_rad.to_degrees();
+
+    (if aspect_deg < 0.0 { aspect_deg + 360.0 } else { aspect_deg }) as f32
+}
@@ -0,0 +1,88 @@
+//! Python bindings for [`gdal_dtm_exporter_lib`], so a Houdini HDA's Python
+//! script can drive an export without shelling out to the CLI binary.
+//!
+//! Built with `abi3-py39`, so one compiled extension module is loadable by
+//! any CPython 3.9+ interpreter -- including the 3.9/3.10/3.11 builds
+//! bundled across recent Houdini versions -- without rebuilding per minor
+//! version.
+
+use std::path::PathBuf;
+
+use gdal_dtm_exporter_lib::formats::{ColorSpace, ExrChannels, ExrCompression, Tonemap};
+use gdal_dtm_exporter_lib::{export_dtm_to_exr, BandSelection, DtmOutputFormat, NodataAs};
+use gdal::raster::ResampleAlg;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+/// Exports `input` to an OpenEXR heightfield under `output_dir`, returning
+/// the output file's path.
+///
+/// Only the knobs a Houdini-side caller is likely to need day to day are
+/// exposed here; everything [`export_dtm_to_exr`] otherwise supports (bbox
+/// cropping, colormaps, graticules, ...) keeps this binding's defaults --
+/// reach for the CLI binary for those until a caller actually asks for them
+/// from Python.
+#[pyfunction]
+#[pyo3(signature = (input, output_dir, window_scale_factor=10, normalize=true, overwrite=true))]
+fn export_dtm_to_exr_py(
+    input: PathBuf,
+    output_dir: PathBuf,
+    window_scale_factor: usize,
+    normalize: bool,
+    overwrite: bool,
+) -> PyResult<String> {
+    let output_path = export_dtm_to_exr(
+        &input,
+        &output_dir,
+        None, // output_name
+        window_scale_factor,
+        None, // bbox
+        normalize,
+        overwrite,
+        false, // flip_y
+        false, // flip_x
+        false, // normalize_per_tile
+        None,  // manual_range
+        None,  // percentile_range
+        1.0,   // vertical_exaggeration
+        None,  // nodata_color
+        NodataAs::Zero,
+        false, // write_aux_xml
+        false, // world_file
+        false, // stats_json
+        ColorSpace::Linear,
+        Tonemap::Linear,  // tonemap
+        2.2,              // gamma
+        None,             // graticule_spacing
+        [1.0, 0.0, 0.0],  // graticule_color
+        0.5,              // graticule_opacity
+        false,            // use_metadata_offset
+        ResampleAlg::Bilinear,
+        0, // tile_overlap
+        None, // fill_voids
+        BandSelection::All,
+        DtmOutputFormat::Exr,
+        ExrCompression::Zip,
+        ExrChannels::Rgb,
+        false,     // build_overviews
+        "AVERAGE", // overview_resample
+        false,     // exr_tiled
+        (128, 128), // exr_tile_size
+        false,     // mipmaps
+        false, // with_mask
+        None,  // colormap
+        None,  // cancel
+        None,  // threads
+        None,  // progress
+    )
+    .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+
+    Ok(output_path.to_string_lossy().into_owned())
+}
+
+/// The `gdal_dtm_exporter_py` Python module.
+#[pymodule]
+fn gdal_dtm_exporter_py(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(export_dtm_to_exr_py, m)?)?;
+    Ok(())
+}
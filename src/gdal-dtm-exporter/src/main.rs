@@ -0,0 +1,109 @@
+// Original prototype for turning a DTM GeoTIFF into a point cloud.
+//
+// This predates the `gdal-dtm-exporter-lib`/`gdal-dtm-exporter-bin` split and is
+// kept around mostly as a reference for the PLY writing bits, which have since
+// been ported into `gdal-dtm-exporter-lib` as `export_dtm_to_ply`. Prefer the
+// `gdal-dtm-exporter-bin` CLI for anything new.
+
+use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use gdal::raster::ResampleAlg;
+use gdal::Dataset;
+
+struct Point {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+fn as_ply_line(point: &Point) -> String {
+    format!("{} {} {}\n", point.x, point.y, point.z)
+}
+
+fn write_ply_header(writer: &mut impl Write, num_points: usize) -> std::io::Result<()> {
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "comment exported by gdal-dtm-exporter (legacy)")?;
+    writeln!(writer, "element vertex {}", num_points)?;
+    writeln!(writer, "property float x")?;
+    writeln!(writer, "property float y")?;
+    writeln!(writer, "property float z")?;
+    writeln!(writer, "end_header")?;
+    Ok(())
+}
+
+fn write_ply_chunk(writer: &mut impl Write, points: &[Point]) -> std::io::Result<()> {
+    for point in points {
+        writer.write_all(as_ply_line(point).as_bytes())?;
+    }
+    Ok(())
+}
+
+fn main() -> gdal::errors::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!("usage: gdal-dtm-exporter <input.tif> <output.ply> [downsample_factor]");
+        std::process::exit(1);
+    }
+
+    let input_path = PathBuf::from(&args[1]);
+    let output_path = PathBuf::from(&args[2]);
+
+    // Downsampling factor: the output point grid is `raster / downsample_factor`
+    // per side. Previously this divided the *tiling* chunk size instead of the
+    // output resolution, so every value other than 1 just changed how many GDAL
+    // reads it took to visit the same full-resolution pixels -- the point count
+    // never actually shrank.
+    let downsample_factor: usize = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(1);
+    // Vertical exaggeration, applied to every sampled elevation.
+    let y_scale: f32 = 1.0;
+
+    let dataset = Dataset::open(&input_path)?;
+    let (raster_w, raster_h) = dataset.raster_size();
+    let band = dataset.rasterband(1)?;
+
+    let output_w = (raster_w / downsample_factor).max(1);
+    let output_h = (raster_h / downsample_factor).max(1);
+
+    let mut points = Vec::with_capacity(output_w * output_h);
+
+    for y_offset in (0..output_h).step_by(output_h) {
+        for x_offset in (0..output_w).step_by(output_w) {
+            let tile_w = output_w - x_offset;
+            let tile_h = output_h - y_offset;
+
+            // Read a `downsample_factor`-times-larger source window than the
+            // destination buffer, so GDAL's resampler -- not this loop --
+            // does the actual shrinking; the output buffer's strides are in
+            // output space throughout, so `px`/`py` never need rescaling.
+            let buffer = band.read_as::<f32>(
+                ((x_offset * downsample_factor) as isize, (y_offset * downsample_factor) as isize),
+                (tile_w * downsample_factor, tile_h * downsample_factor),
+                (tile_w, tile_h),
+                Some(ResampleAlg::Bilinear),
+            )?;
+
+            for (i, &elevation) in buffer.data().iter().enumerate() {
+                let px = (x_offset + i % tile_w) as f32;
+                let py = (y_offset + i / tile_w) as f32;
+                points.push(Point {
+                    x: px,
+                    y: elevation * y_scale,
+                    z: py,
+                });
+            }
+        }
+    }
+
+    let file = File::create(&output_path).expect("could not create output file");
+    let mut writer = BufWriter::new(file);
+    write_ply_header(&mut writer, points.len()).expect("could not write PLY header");
+    write_ply_chunk(&mut writer, &points).expect("could not write PLY body");
+
+    println!("Wrote {} points to {}", points.len(), output_path.display());
+
+    Ok(())
+}